@@ -1,30 +1,56 @@
 mod cli_args;
 mod commands;
 mod output;
+mod preview;
 mod watch;
 
 use anyhow::{Context, Result};
 use clap::{CommandFactory, Parser};
 use colored::*;
 use log;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
 // Removed unused Arc import
 
 // Corrected import: Added GenerateArgs
 use cli_args::{Cli, Commands, FormatOutputOpts, GenerateArgs, ProjectConfigOpts};
+use xcontext_core::config::PrettyMode;
 use xcontext_core::{AppError, Config}; // Use Config from core crate
 
 fn main() {
     let cli_args = Cli::parse();
 
-    setup_logging(cli_args.quiet, cli_args.verbose);
+    let no_color = cli_args.no_color || std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    if no_color {
+        colored::control::set_override(false);
+    }
+    setup_logging(
+        cli_args.quiet,
+        cli_args.verbose,
+        &cli_args.log_format,
+        no_color,
+        cli_args.strict,
+    );
 
     let quiet = cli_args.quiet;
     let verbose = cli_args.verbose;
+    let strict = cli_args.strict;
 
     log::debug!("CLI args parsed: {:?}", cli_args);
 
     let exit_code = match run_app(cli_args, quiet, verbose) {
+        Ok(_) if strict && warning_count() > 0 => {
+            let message = format!(
+                "{} warning(s) were emitted and --strict is set.",
+                warning_count()
+            );
+            if !quiet {
+                eprintln!("{} {}\n", "Error:".red().bold(), message);
+            }
+            7
+        }
         Ok(_) => {
             log::info!("Application finished successfully.");
             0
@@ -76,7 +102,43 @@ fn main() {
     process::exit(exit_code);
 }
 
-fn setup_logging(quiet: bool, verbose: u8) {
+// Warnings seen during this run, counted for `--strict` whether or not they were displayed
+static WARNINGS_EMITTED: AtomicUsize = AtomicUsize::new(0);
+
+// Counts a warning that was printed directly rather than through `log::warn!`
+pub(crate) fn record_warning() {
+    WARNINGS_EMITTED.fetch_add(1, Ordering::Relaxed);
+}
+
+fn warning_count() -> usize {
+    WARNINGS_EMITTED.load(Ordering::Relaxed)
+}
+
+// Wraps the env_logger output so warnings are counted even when their level is filtered out
+struct WarningCountingLogger {
+    inner: env_logger::Logger,
+}
+
+impl log::Log for WarningCountingLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Warn || self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if record.level() <= log::Level::Warn {
+            record_warning();
+        }
+        if self.inner.enabled(record.metadata()) {
+            self.inner.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+fn setup_logging(quiet: bool, verbose: u8, log_format: &str, no_color: bool, strict: bool) {
     let log_level = if quiet {
         log::LevelFilter::Off // Turn off logging completely if quiet
     } else {
@@ -87,10 +149,35 @@ fn setup_logging(quiet: bool, verbose: u8) {
             _ => log::LevelFilter::Trace, // -vvv+: Show all levels
         }
     };
-    env_logger::Builder::new()
-        .filter_level(log_level)
-        .format_timestamp(None) // Keep logs clean
-        .init();
+    let mut builder = env_logger::Builder::new();
+    builder.filter_level(log_level);
+    if no_color {
+        builder.write_style(env_logger::WriteStyle::Never);
+    }
+    if log_format == "json" {
+        // One JSON object per line for log-aggregation pipelines
+        builder.format(|buf, record| {
+            let line = serde_json::json!({
+                "timestamp": buf.timestamp().to_string(),
+                "level": record.level().to_string(),
+                "target": record.target(),
+                "message": record.args().to_string(),
+            });
+            writeln!(buf, "{}", line)
+        });
+    } else {
+        builder.format_timestamp(None); // Keep logs clean
+    }
+    if strict {
+        // Even with --quiet, warnings must reach the logger to be counted
+        log::set_max_level(log_level.max(log::LevelFilter::Warn));
+        log::set_boxed_logger(Box::new(WarningCountingLogger {
+            inner: builder.build(),
+        }))
+        .expect("Logger is initialized only once");
+    } else {
+        builder.init();
+    }
     log::trace!("Logger initialized with level: {:?}", log_level);
 }
 
@@ -112,9 +199,8 @@ fn run_app(cli: Cli, quiet: bool, verbose: u8) -> Result<()> {
                 }
                 Commands::Config(args) => {
                     log::debug!("Executing 'config' command...");
-                    let temp_opts = ProjectConfigOpts::default();
                     let project_root =
-                        Config::determine_project_root(temp_opts.project_root.as_ref())
+                        Config::determine_project_root(args.project_config.project_root.as_ref())
                             .context("Failed to determine project root for config command")?;
                     commands::config::handle_config_command(&args, &project_root, quiet)?;
                 }
@@ -124,7 +210,7 @@ fn run_app(cli: Cli, quiet: bool, verbose: u8) -> Result<()> {
                 }
                 Commands::Generate(args) => {
                     log::debug!("Executing 'generate' command...");
-                    commands::generate::handle_generate_command(args, quiet, verbose)?;
+                    commands::generate::handle_generate_command(*args, quiet, verbose)?;
                 }
                 Commands::Watch(args) => {
                     log::debug!("Executing 'watch' command...");
@@ -143,16 +229,44 @@ fn run_app(cli: Cli, quiet: bool, verbose: u8) -> Result<()> {
                     log::debug!("Executing 'debug' command...");
                     commands::debug::handle_debug_command(args, quiet, verbose)?;
                 }
+                Commands::Doctor(args) => {
+                    log::debug!("Executing 'doctor' command...");
+                    commands::doctor::handle_doctor_command(args, quiet)?;
+                }
                 Commands::Quick(args) => {
                     log::debug!("Executing 'quick' command...");
                     commands::quick::handle_quick_command(args, quiet, verbose)?;
                 }
+                Commands::Info(args) => {
+                    log::debug!("Executing 'info' command...");
+                    commands::info::handle_info_command(&args)?;
+                }
+                Commands::Merge(args) => {
+                    log::debug!("Executing 'merge' command...");
+                    commands::merge::handle_merge_command(&args, quiet)?;
+                }
             }
         }
     }
     Ok(())
 }
 
+fn save_path_from_arg(save: &Option<Option<PathBuf>>) -> Option<&Path> {
+    save.as_ref().and_then(|p| p.as_deref())
+}
+
+// Infers the format from the save path's extension; stdout output falls back to JSON
+fn resolve_auto_format(config: &mut Config, save_path: Option<&Path>) {
+    if config.output.format != "auto" {
+        return;
+    }
+    let inferred = save_path
+        .and_then(output::format_from_path)
+        .unwrap_or("json");
+    log::debug!("Resolved 'auto' output format to '{}'", inferred);
+    config.output.format = inferred.to_string();
+}
+
 // Kept this function as it seems used by load_config_for_command
 fn merge_config_with_cli_overrides(mut config: Config, args: &GenerateArgs) -> Config {
     log::trace!("Applying generate command CLI overrides to config...");
@@ -165,6 +279,8 @@ fn merge_config_with_cli_overrides(mut config: Config, args: &GenerateArgs) -> C
     if let Some(format) = &args.format_output.format {
         config.output.format = format.clone();
     }
+    resolve_auto_format(&mut config, save_path_from_arg(&args.save));
+    config.save.cli_save_path = save_path_from_arg(&args.save).map(Path::to_path_buf);
     // Apply JSON minify logic based on flags and format
     config.output.json_minify = if config.output.format == "json" {
         !args.format_output.disable_json_minify // default to true (minify) unless disable flag is set
@@ -177,6 +293,14 @@ fn merge_config_with_cli_overrides(mut config: Config, args: &GenerateArgs) -> C
     } else {
         false // Default irrelevant for non-XML
     };
+    if let Some(mode) = args
+        .format_output
+        .pretty
+        .as_deref()
+        .and_then(PrettyMode::from_name)
+    {
+        config.output.pretty = mode;
+    }
 
     // Exclusion Overrides
     if args.exclusion.exclude_project_name {
@@ -217,6 +341,26 @@ fn merge_config_with_cli_overrides(mut config: Config, args: &GenerateArgs) -> C
     if args.section_toggles.enable_rules {
         config.rules.enabled = true;
     }
+    if !args.rule_include.is_empty() || !args.rule_exclude.is_empty() {
+        let available = xcontext_core::list_static_rules();
+        for stem in args.rule_include.iter().chain(&args.rule_exclude) {
+            let normalized = xcontext_core::rules::normalize_static_rule_stem(stem);
+            if !available.iter().any(|known| known == normalized) {
+                log::warn!(
+                    "Unknown rule stem '{}' (see 'xcontext show rules --available').",
+                    stem
+                );
+            }
+        }
+        config
+            .rules
+            .include
+            .extend(args.rule_include.iter().cloned());
+        config
+            .rules
+            .exclude
+            .extend(args.rule_exclude.iter().cloned());
+    }
     if args.section_toggles.disable_docs {
         config.docs.enabled = false;
     }
@@ -237,27 +381,60 @@ fn merge_config_with_cli_overrides(mut config: Config, args: &GenerateArgs) -> C
     if args.ignore_toggles.enable_builtin_ignore {
         config.general.enable_builtin_ignore = true;
     }
-
-    // Filter Overrides
-    if !args.filters.tree_include.is_empty() {
-        config.tree.include = Some(args.filters.tree_include.clone());
-    }
-    if !args.filters.tree_exclude.is_empty() {
-        config.tree.exclude = Some(args.filters.tree_exclude.clone());
-    }
-    if !args.filters.source_include.is_empty() {
-        config.source.include = Some(args.filters.source_include.clone());
+    if args.ignore_toggles.exclude_generated {
+        config.common_filters.exclude_generated = true;
     }
-    if !args.filters.source_exclude.is_empty() {
-        config.source.exclude = Some(args.filters.source_exclude.clone());
+    if args.ignore_toggles.exclude_untracked {
+        config.general.exclude_untracked = true;
     }
-    if !args.filters.docs_include.is_empty() {
-        config.docs.include = Some(args.filters.docs_include.clone());
+    config.general.cli_ignore_files = args.ignore_toggles.ignore_files.clone();
+    if args.fail_on_empty {
+        config.general.fail_on_empty = true;
     }
-    if !args.filters.docs_exclude.is_empty() {
-        config.docs.exclude = Some(args.filters.docs_exclude.clone());
+    if args.overview {
+        config.tree.enabled = true; // The overview is the tree plus metrics
     }
 
+    // Filter Overrides
+    let append = args.filters.append_filters;
+    let common = &config.common_filters;
+    apply_filter_override(
+        &mut config.tree.include,
+        &common.include,
+        &args.filters.tree_include,
+        append,
+    );
+    apply_filter_override(
+        &mut config.tree.exclude,
+        &common.exclude,
+        &args.filters.tree_exclude,
+        append,
+    );
+    apply_filter_override(
+        &mut config.source.include,
+        &common.include,
+        &args.filters.source_include,
+        append,
+    );
+    apply_filter_override(
+        &mut config.source.exclude,
+        &common.exclude,
+        &args.filters.source_exclude,
+        append,
+    );
+    apply_filter_override(
+        &mut config.docs.include,
+        &common.include,
+        &args.filters.docs_include,
+        append,
+    );
+    apply_filter_override(
+        &mut config.docs.exclude,
+        &common.exclude,
+        &args.filters.docs_exclude,
+        append,
+    );
+
     // Meta Override
     if !args.meta_override.add_meta.is_empty() {
         log::trace!("Applying meta overrides: {:?}", args.meta_override.add_meta);
@@ -272,6 +449,26 @@ fn merge_config_with_cli_overrides(mut config: Config, args: &GenerateArgs) -> C
     config
 }
 
+// CLI patterns replace the section's list, or with --append-filters extend its effective list
+// (the section's own patterns, else the common_filters ones it inherits)
+fn apply_filter_override(
+    section: &mut Option<Vec<String>>,
+    common: &[String],
+    cli_patterns: &[String],
+    append: bool,
+) {
+    if cli_patterns.is_empty() {
+        return;
+    }
+    let mut patterns = if append {
+        section.clone().unwrap_or_else(|| common.to_vec())
+    } else {
+        Vec::new()
+    };
+    patterns.extend(cli_patterns.iter().cloned());
+    *section = Some(patterns);
+}
+
 // Helper function to load config considering CLI options
 // Kept public as it's used by multiple command modules
 pub fn load_config_for_command(
@@ -295,6 +492,21 @@ pub fn load_config_for_command(
         None => Config::default(),
     };
 
+    // Profile overrides sit between the config file and CLI overrides
+    let profile_name = generate_args
+        .and_then(|a| a.profile.as_deref())
+        .or_else(|| watch_args.and_then(|a| a.profile.as_deref()));
+    if let Some(name) = profile_name {
+        config = config
+            .apply_profile(name)
+            .with_context(|| format!("Failed to apply profile '{}'", name))?;
+    }
+    if let Some(assignments) = generate_args.map(|a| &a.set).filter(|s| !s.is_empty()) {
+        config = config
+            .apply_overrides(assignments)
+            .context("Failed to apply --set overrides")?;
+    }
+
     // Apply overrides from GenerateArgs if provided
     if let Some(gen_args) = generate_args {
         config = merge_config_with_cli_overrides(config, gen_args);
@@ -304,10 +516,13 @@ pub fn load_config_for_command(
             config.general.project_name = Some(name.clone());
         }
         // Apply format overrides if present
+        if let Some(format) = format_override.and_then(|f| f.format.as_ref()) {
+            config.output.format = format.clone();
+        }
+        let watch_save_path = watch_args.and_then(|w| save_path_from_arg(&w.save));
+        resolve_auto_format(&mut config, watch_save_path);
+        config.save.cli_save_path = watch_save_path.map(Path::to_path_buf);
         if let Some(fmt_opts) = format_override {
-            if let Some(format) = &fmt_opts.format {
-                config.output.format = format.clone();
-            }
             config.output.json_minify = if config.output.format == "json" {
                 !fmt_opts.disable_json_minify
             } else {
@@ -318,16 +533,29 @@ pub fn load_config_for_command(
             } else {
                 false
             };
+            if let Some(mode) = fmt_opts.pretty.as_deref().and_then(PrettyMode::from_name) {
+                config.output.pretty = mode;
+            }
         }
         // Apply watch-specific overrides if present
         if let Some(w_args) = watch_args {
             if let Some(delay) = &w_args.watch_delay {
                 config.watch.delay = delay.clone();
             }
+            if w_args.poll {
+                config.watch.force_poll = true;
+            }
+            if let Some(delay) = &w_args.initial_delay {
+                config.watch.initial_delay = Some(delay.clone());
+            }
             // Note: watch also uses format_override logic handled above if needed
         }
     }
 
+    config
+        .resolve_filter_commands(project_root)
+        .context("Failed to resolve include/exclude commands")?;
+
     // Ensure project name is set (fallback to directory name)
     config.general.project_name = Some(config.get_effective_project_name(project_root));
 
@@ -39,12 +39,21 @@ pub struct ProjectConfigOpts {
 
 #[derive(Args, Debug, Clone, Default)]
 pub struct FormatOutputOpts {
-    #[arg(short = 'f', long, help = "Set the output format.", value_name = "FORMAT", value_parser = ["json", "yaml", "xml"], help_heading = "Output Formatting")]
+    #[arg(short = 'f', long, help = "Set the output format ('auto' infers it from the --save file extension).", value_name = "FORMAT", value_parser = ["json", "yaml", "xml", "doc-tags", "markdown", "md", "auto"], help_heading = "Output Formatting")]
     pub format: Option<String>,
 
     #[arg(
         long,
-        help = "Ensure JSON output is compact (minified) [default].",
+        value_name = "MODE",
+        value_parser = ["auto", "always", "never"],
+        help = "Pretty-print output in every format: auto (per-format defaults), always, or never.",
+        help_heading = "Output Formatting"
+    )]
+    pub pretty: Option<String>,
+
+    #[arg(
+        long,
+        help = "Ensure JSON output is compact (minified) [default]. Deprecated: use --pretty.",
         conflicts_with = "disable_json_minify",
         help_heading = "Output Formatting"
     )]
@@ -52,7 +61,7 @@ pub struct FormatOutputOpts {
 
     #[arg(
         long,
-        help = "Ensure JSON output is pretty-printed (readable).",
+        help = "Ensure JSON output is pretty-printed (readable). Deprecated: use --pretty.",
         conflicts_with = "enable_json_minify",
         help_heading = "Output Formatting"
     )]
@@ -60,7 +69,7 @@ pub struct FormatOutputOpts {
 
     #[arg(
         long,
-        help = "Ensure XML output is pretty-printed (readable).",
+        help = "Ensure XML output is pretty-printed (readable). Deprecated: use --pretty.",
         conflicts_with = "disable_xml_pretty",
         help_heading = "Output Formatting"
     )]
@@ -68,11 +77,20 @@ pub struct FormatOutputOpts {
 
     #[arg(
         long,
-        help = "Ensure XML output is compact [default].",
+        help = "Ensure XML output is compact [default]. Deprecated: use --pretty.",
         conflicts_with = "enable_xml_pretty",
         help_heading = "Output Formatting"
     )]
     pub disable_xml_pretty: bool,
+
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        value_parser = ["json", "yaml", "xml"],
+        help = "Retry with this format (and warn) when serializing in the primary format fails, e.g. XML for a top-level list.",
+        help_heading = "Output Formatting"
+    )]
+    pub format_fallback: Option<String>,
 }
 
 #[derive(Parser, Debug)]
@@ -99,6 +117,30 @@ pub struct Cli {
         help = "Silence informational messages and warnings."
     )]
     pub quiet: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "FORMAT",
+        value_parser = ["text", "json"],
+        default_value = "text",
+        help = "Set the log output format ('json' emits one JSON object per line)."
+    )]
+    pub log_format: String,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Disable colored output (also enabled by a non-empty NO_COLOR environment variable)."
+    )]
+    pub no_color: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Exit with a non-zero code if any warning was emitted (the run still completes)."
+    )]
+    pub strict: bool,
 }
 
 #[derive(Subcommand, Debug, Clone)]
@@ -108,7 +150,7 @@ pub enum Commands {
         visible_alias = "gen",
         about = "Generate the full project context."
     )]
-    Generate(GenerateArgs),
+    Generate(Box<GenerateArgs>),
 
     #[command(
         visible_alias = "w",
@@ -134,12 +176,27 @@ pub enum Commands {
     )]
     Debug(DebugArgs),
 
+    #[command(
+        about = "Check environment readiness (config, git, save directory, watch delay, imports)."
+    )]
+    Doctor(DoctorArgs),
+
     #[command(
         visible_alias = "q",
         about = "Quickly extract content of files matching a pattern."
     )]
     Quick(QuickArgs),
 
+    #[command(
+        about = "Reassemble the chunk files in a directory (JSON chunks, whatever their names, or *_chunks.yaml) into one source file list."
+    )]
+    Merge(MergeArgs),
+
+    #[command(
+        about = "Print the version, output formats, commands and features of this build (JSON by default)."
+    )]
+    Info(InfoArgs),
+
     #[command(about = "Generate or save shell completion scripts.")]
     Completion(CompletionArgs),
 
@@ -172,19 +229,134 @@ pub struct GenerateArgs {
         short = 's', long, value_name = "SAVE_DIR",
         num_args = 0..=1,
         help_heading = "Output Control",
-        help = "Save context. Optional SAVE_DIR overrides config/default logic.",
+        help = "Save context. Optional SAVE_DIR overrides config/default logic; a path ending in the format's extension is used as the file.",
     )]
     pub save: Option<Option<PathBuf>>,
 
     #[arg(
         short = 'c',
         long,
-        help = "Split source content into chunks (e.g., '5MB', '1024kb'). Requires JSON format.",
+        help = "Split source content into chunks (e.g., '5MB', '1024kb'). Requires JSON or YAML format (YAML writes one multi-document file).",
         value_name = "SIZE_STRING",
         help_heading = "Output Control"
     )]
     pub chunks: Option<String>,
 
+    #[arg(
+        long = "also-format",
+        value_name = "FORMAT",
+        value_parser = ["json", "yaml", "xml", "doc-tags", "markdown", "md"],
+        action = clap::ArgAction::Append,
+        requires = "save",
+        help = "Also save the context in an additional format alongside the primary one (repeatable). Requires --save.",
+        help_heading = "Output Control"
+    )]
+    pub also_format: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Suppress the 'saved to' confirmation messages (warnings are still shown).",
+        help_heading = "Output Control"
+    )]
+    pub no_banner: bool,
+
+    #[arg(
+        long,
+        value_name = "TOKENS",
+        help = "Drop source files until their estimated tokens fit the budget ([source].priority files are kept first). Only source file contents count; docs, tree and metadata are not included.",
+        help_heading = "Output Control"
+    )]
+    pub max_tokens: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Exit with an error when no source or docs files are gathered.",
+        help_heading = "Output Control"
+    )]
+    pub fail_on_empty: bool,
+
+    #[arg(
+        long = "rule-include",
+        value_name = "STEM",
+        action = clap::ArgAction::Append,
+        help = "Add an embedded rule stem to [rules].include for this run (repeatable).",
+        help_heading = "Section Toggles"
+    )]
+    pub rule_include: Vec<String>,
+
+    #[arg(
+        long = "rule-exclude",
+        value_name = "STEM",
+        action = clap::ArgAction::Append,
+        help = "Add a rule stem to [rules].exclude for this run, e.g. 'documentation' (repeatable).",
+        help_heading = "Section Toggles"
+    )]
+    pub rule_exclude: Vec<String>,
+
+    #[arg(
+        long,
+        conflicts_with = "chunks",
+        help = "Output only the tree plus a size/token metrics summary, without file contents.",
+        help_heading = "Output Control"
+    )]
+    pub overview: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["chunks", "overview"],
+        help = "Output only the included file paths with their sizes and SHA-256 hashes (no content), saved as '<base>.manifest.<ext>' with --save.",
+        help_heading = "Output Control"
+    )]
+    pub manifest_only: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["chunks", "overview", "manifest_only", "pretty_preview"],
+        help = "Output only the docs as one Markdown bundle (README first, with a table of contents from headings), saved as '<base>.docs.md' with --save; docs.max_files applies.",
+        help_heading = "Output Control"
+    )]
+    pub docs_only: bool,
+
+    #[arg(
+        long,
+        help = "Also compute file/line/token metrics from the files gathered for this run (no second walk), saved as '<base>.metrics.json' with --save or summarized on stderr.",
+        help_heading = "Output Control"
+    )]
+    pub with_metrics: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["save", "manifest_only"],
+        help = "When stdout is a terminal, render the context as a readable preview with syntax-highlighted files; plain output otherwise.",
+        help_heading = "Output Control"
+    )]
+    pub pretty_preview: bool,
+
+    #[arg(
+        long,
+        requires = "save",
+        help = "Write a '<base>.xcontext.prov' provenance file (SHA-256, version, timestamp; HMAC when XCONTEXT_SIGNING_KEY is set).",
+        help_heading = "Output Control"
+    )]
+    pub sign: bool,
+
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Apply the named [profiles.NAME] overrides from the config file.",
+        help_heading = "Project Setup"
+    )]
+    pub profile: Option<String>,
+
+    #[arg(
+        long = "set",
+        value_name = "KEY=VALUE",
+        action = clap::ArgAction::Append,
+        help = "Override a config value for this run by dotted path, e.g. 'output.format=yaml' or 'tree.enabled=false' (repeatable).",
+        help_heading = "Project Setup"
+    )]
+    pub set: Vec<String>,
+
     #[clap(flatten)]
     pub exclusion: ExclusionGroup,
     #[clap(flatten)]
@@ -211,6 +383,32 @@ pub struct WatchArgs {
     )]
     pub watch_delay: Option<String>,
 
+    #[arg(
+        long,
+        value_name = "DELAY_STRING",
+        help = "Wait this long before the first generation, e.g. to let a checkout settle [default: none]"
+    )]
+    pub initial_delay: Option<String>,
+
+    #[arg(
+        long,
+        help = "Poll for changes instead of using native file events (for NFS mounts and Docker volumes); interval from watch.poll_interval."
+    )]
+    pub poll: bool,
+
+    #[arg(
+        long,
+        help = "After the initial full context, emit only the source/docs files that changed."
+    )]
+    pub delta: bool,
+
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Apply the named [profiles.NAME] overrides from the config file."
+    )]
+    pub profile: Option<String>,
+
     #[arg( short = 's', long, value_name = "SAVE_DIR", num_args = 0..=1, help = "Save context on change. Optional SAVE_DIR overrides config/default logic.", )]
     pub save: Option<Option<PathBuf>>,
 }
@@ -230,15 +428,60 @@ pub enum ShowItem {
     #[command(about = "Show specific metadata key or list available keys.")]
     Meta { key: Option<String> },
     #[command(about = "Show content of all metadata keys (default: pretty text).")]
-    Metas {},
+    Metas {
+        #[arg(long, help = "Print only the number of metadata keys.")]
+        summary: bool,
+    },
     #[command(about = "Show specific prompt or list available prompt names.")]
     Prompt { name: Option<String> },
     #[command(about = "Show content of all prompts (default: pretty text).")]
-    Prompts {},
+    Prompts {
+        #[arg(
+            long,
+            help = "Only list the built-in prompts (keys prefixed 'static:')."
+        )]
+        static_only: bool,
+        #[arg(
+            long,
+            value_name = "N",
+            help = "Truncate each prompt to its first N characters for a compact listing."
+        )]
+        preview: Option<usize>,
+        #[arg(
+            long,
+            conflicts_with = "preview",
+            help = "Print only counts (prompts in total and per origin) instead of their text."
+        )]
+        summary: bool,
+    },
     #[command(about = "Show specific rule set/list or list available names.")]
     Rule { name: Option<String> },
     #[command(about = "Show content of all rule sets/lists (default: pretty text).")]
-    Rules {},
+    Rules {
+        #[arg(
+            long,
+            help = "List the embedded static rule names usable in [rules].include instead."
+        )]
+        available: bool,
+        #[arg(
+            long,
+            conflicts_with = "available",
+            help = "Print only counts (rule sets, total rules, rule sets per origin) instead of their content."
+        )]
+        summary: bool,
+    },
+    #[command(
+        about = "Show the effective ignore set: built-in patterns, gitignore settings and configured excludes."
+    )]
+    Ignores {
+        #[arg(
+            long = "ignore-file",
+            value_name = "PATH",
+            action = clap::ArgAction::Append,
+            help = "Include an extra gitignore-format file in the report, as generate --ignore-file would apply it (repeatable)."
+        )]
+        ignore_files: Vec<PathBuf>,
+    },
 }
 
 #[derive(Args, Debug, Clone)]
@@ -247,6 +490,48 @@ pub struct MetricsArgs {
     pub project_config: ProjectConfigOpts,
     #[clap(flatten)]
     pub format_output: FormatOutputOpts,
+
+    #[arg(
+        long,
+        help = "Estimate the input cost of sending the project as context (needs --price-per-1k or --model)."
+    )]
+    pub cost: bool,
+    #[arg(
+        long = "price-per-1k",
+        value_name = "USD",
+        requires = "cost",
+        conflicts_with = "model",
+        help = "Input token price in USD per 1,000 tokens."
+    )]
+    pub price_per_1k: Option<f64>,
+    #[arg(
+        long,
+        value_name = "NAME",
+        requires = "cost",
+        help = "Use the built-in approximate input price of a common model (e.g., 'gpt-4o')."
+    )]
+    pub model: Option<String>,
+
+    #[arg(
+        long = "by-dir",
+        value_name = "DEPTH",
+        num_args = 0..=1,
+        default_missing_value = "1",
+        help = "Aggregate lines/bytes/tokens per directory, grouped at DEPTH path components [default: 1]."
+    )]
+    pub by_dir: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Estimate tokens as bytes/4 instead of running the cl100k_base tokenizer (also the automatic fallback when it can't load)."
+    )]
+    pub approx_tokens: bool,
+
+    #[arg(
+        long,
+        help = "Measure every gathered file, ignoring source/docs include/exclude and content filters (gitignore and built-in ignores still apply)."
+    )]
+    pub include_all: bool,
 }
 
 #[derive(Args, Debug, Clone)]
@@ -255,6 +540,22 @@ pub struct DebugArgs {
     pub project_config: ProjectConfigOpts,
     #[clap(flatten)]
     pub format_output: FormatOutputOpts,
+    #[command(subcommand)]
+    pub action: Option<DebugAction>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct DoctorArgs {
+    #[clap(flatten)]
+    pub project_config: ProjectConfigOpts,
+    #[clap(flatten)]
+    pub format_output: FormatOutputOpts,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum DebugAction {
+    #[command(about = "Explain why a path is included in or excluded from the context.")]
+    Explain { path: PathBuf },
 }
 
 #[derive(Args, Debug, Clone)]
@@ -268,6 +569,62 @@ pub struct QuickArgs {
         help = "Glob pattern (e.g., 'src/**/*.rs', 'data/', 'file.txt')"
     )]
     pub pattern: String,
+
+    #[arg(
+        long,
+        help = "Only list matching paths with their sizes and a total, without reading contents."
+    )]
+    pub count: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "count",
+        help = "Write each matched file to stdout as it is read instead of buffering."
+    )]
+    pub stream: bool,
+
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        value_parser = ["jsonl", "concat"],
+        default_value = "jsonl",
+        requires = "stream",
+        help = "Record format for --stream: one JSON object per line, or concatenated contents with path headers."
+    )]
+    pub stream_format: String,
+
+    #[arg(
+        long = "root",
+        value_name = "PATH",
+        action = clap::ArgAction::Append,
+        help = "Search this directory instead of the project root (repeatable; with several roots, keys are prefixed with each root's path below their common parent)."
+    )]
+    pub root: Vec<PathBuf>,
+
+    #[arg(long, help = "Emit absolute file paths instead of root-relative ones.")]
+    pub absolute_paths: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct InfoArgs {
+    #[clap(flatten)]
+    pub format_output: FormatOutputOpts,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct MergeArgs {
+    #[clap(flatten)]
+    pub format_output: FormatOutputOpts,
+    #[arg(help = "Directory containing the chunk files")]
+    pub dir: PathBuf,
+
+    #[arg(
+        short = 'o',
+        long,
+        value_name = "FILE",
+        help = "Write the merged output to FILE instead of stdout."
+    )]
+    pub output: Option<PathBuf>,
 }
 
 #[derive(Args, Debug, Clone)]
@@ -287,11 +644,30 @@ pub struct CompletionArgs {
 
 #[derive(Args, Debug, Clone)]
 pub struct ConfigArgs {
+    #[clap(flatten)]
+    pub project_config: ProjectConfigOpts,
+
     #[arg(
         long,
         help = "Save default config structure to default path (prompts overwrite)."
     )]
     pub save: bool,
+
+    #[arg(
+        long,
+        help = "Print the path of the config file that would be loaded, then exit.",
+        conflicts_with = "save"
+    )]
+    pub which: bool,
+
+    #[arg(
+        long,
+        value_name = "SECTIONS",
+        value_delimiter = ',',
+        conflicts_with_all = ["save", "which"],
+        help = "Print only the given default config sections (comma-separated, e.g. 'source,output')."
+    )]
+    pub template: Vec<String>,
 }
 
 #[derive(Args, Debug, Clone)]
@@ -434,6 +810,29 @@ pub struct IgnoreTogglesGroup {
         help_heading = "Ignore Rules"
     )]
     pub disable_builtin_ignore: bool,
+
+    #[arg(
+        long,
+        help = "Exclude files that look generated or minified (long lines, '.min.' names, generated headers, source maps).",
+        help_heading = "Ignore Rules"
+    )]
+    pub exclude_generated: bool,
+
+    #[arg(
+        long,
+        help = "Exclude files git reports as untracked from source/docs (requires a git repository).",
+        help_heading = "Ignore Rules"
+    )]
+    pub exclude_untracked: bool,
+
+    #[arg(
+        long = "ignore-file",
+        value_name = "PATH",
+        action = clap::ArgAction::Append,
+        help = "Apply an extra gitignore-format file for this run only (repeatable).",
+        help_heading = "Ignore Rules"
+    )]
+    pub ignore_files: Vec<PathBuf>,
 }
 
 #[derive(Args, Debug, Clone, Default)]
@@ -452,6 +851,13 @@ pub struct FilterGroup {
     pub docs_include: Vec<String>,
     #[arg(long = "docs-exclude", value_name = "PATTERN", action = clap::ArgAction::Append, help = "Add exclude path/glob pattern for documentation files.", help_heading = "Content Filtering")]
     pub docs_exclude: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Add the --*-include/--*-exclude patterns to the configured lists instead of replacing them.",
+        help_heading = "Content Filtering"
+    )]
+    pub append_filters: bool,
 }
 
 #[derive(Args, Debug, Clone, Default)]
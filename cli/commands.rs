@@ -1,7 +1,10 @@
 pub mod completion;
 pub mod config;
 pub mod debug;
+pub mod doctor;
 pub mod generate;
+pub mod info;
+pub mod merge;
 pub mod metrics;
 pub mod quick;
 pub mod show;
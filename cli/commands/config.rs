@@ -12,6 +12,13 @@ use xcontext_core::{
 };
 
 pub fn handle_config_command(args: &ConfigArgs, project_root: &Path, quiet: bool) -> Result<()> {
+    if args.which {
+        return print_resolved_config_path(args, project_root);
+    }
+    if !args.template.is_empty() {
+        return print_config_template(&args.template);
+    }
+
     let determined_name = project_root
         .file_name()
         .map(|n| n.to_string_lossy().to_string())
@@ -40,6 +47,46 @@ pub fn handle_config_command(args: &ConfigArgs, project_root: &Path, quiet: bool
     }
 }
 
+fn print_resolved_config_path(args: &ConfigArgs, project_root: &Path) -> Result<()> {
+    let config_path = Config::resolve_config_path(
+        project_root,
+        args.project_config.context_file.as_ref(),
+        args.project_config.disable_context_file,
+    )
+    .context("Failed to resolve configuration path")?;
+
+    match config_path {
+        Some(path) => {
+            let absolute_path = path.canonicalize().unwrap_or(path);
+            println!("{}", absolute_path.display());
+        }
+        None => println!("none (using defaults)"),
+    }
+    Ok(())
+}
+
+fn print_config_template(sections: &[String]) -> Result<()> {
+    let defaults = toml::Table::try_from(Config::default())
+        .context("Failed to convert default config to a TOML table")?;
+
+    let mut template = toml::Table::new();
+    for section in sections.iter().map(|s| s.trim()) {
+        let value = defaults.get(section).with_context(|| {
+            format!(
+                "Unknown config section '{}'. Available: {}",
+                section,
+                defaults.keys().cloned().collect::<Vec<_>>().join(", ")
+            )
+        })?;
+        template.insert(section.to_string(), value.clone());
+    }
+
+    let toml_string =
+        toml::to_string_pretty(&template).context("Failed to serialize config template to TOML")?;
+    print!("{}", toml_string);
+    Ok(())
+}
+
 fn save_config_to_path(config: &Config, path: &Path, quiet: bool) -> Result<()> {
     if path.exists() {
         if quiet {
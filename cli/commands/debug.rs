@@ -1,4 +1,4 @@
-use crate::cli_args::DebugArgs; // Removed unused FormatOutputOpts
+use crate::cli_args::{DebugAction, DebugArgs}; // Removed unused FormatOutputOpts
 use crate::load_config_for_command;
 use crate::output::print_data_or_text;
 use anyhow::{Context, Result};
@@ -7,9 +7,11 @@ use log;
 use pathdiff; // Added use
 use serde::Serialize;
 use std::collections::BTreeMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use toml; // Added use
-use xcontext_core::{self as core, Config, FileInfo, ResolvedRules}; // Removed unused 'config' import alias
+use xcontext_core::{
+    self as core, Config, FileInfo, FilterDecision, PathExplanation, ResolvedRules,
+}; // Removed unused 'config' import alias
 
 #[derive(Debug, Serialize)]
 struct DebugInfo<'a> {
@@ -35,10 +37,17 @@ pub fn handle_debug_command(args: DebugArgs, quiet: bool, _verbose: u8) -> Resul
     )
     .context("Failed to load configuration for debug command")?;
 
+    if let Some(DebugAction::Explain { path }) = &args.action {
+        return handle_explain(&args, &project_root, &config, path);
+    }
+
     log::debug!("Debug: Gathering file lists...");
-    let (source_files, docs_files, tree_path_types) =
-        core::gather_files_and_tree(&project_root, &config, quiet)
-            .context("Failed to gather file lists for debug")?;
+    let core::GatherResult {
+        source_files,
+        docs_files,
+        tree_path_types,
+    } = core::gather_files_and_tree(&project_root, &config, quiet)
+        .context("Failed to gather file lists for debug")?;
     log::debug!("Debug: File lists gathered.");
 
     log::debug!("Debug: Detecting project characteristics...");
@@ -47,9 +56,18 @@ pub fn handle_debug_command(args: DebugArgs, quiet: bool, _verbose: u8) -> Resul
     log::debug!("Debug: Characteristics detected.");
 
     log::debug!("Debug: Resolving rules...");
-    let resolved_rules =
-        core::config::resolve_rules(&config.rules, &project_root, &project_characteristics)
-            .context("Failed to resolve rules for debug")?;
+    let gathered_paths: Vec<&Path> = source_files
+        .iter()
+        .chain(&docs_files)
+        .map(|f| f.path.as_path())
+        .collect();
+    let resolved_rules = core::config::resolve_rules(
+        &config.rules,
+        &project_root,
+        &project_characteristics,
+        &gathered_paths,
+    )
+    .context("Failed to resolve rules for debug")?;
     log::debug!("Debug: Rules resolved.");
 
     let debug_data = DebugInfo {
@@ -76,6 +94,96 @@ pub fn handle_debug_command(args: DebugArgs, quiet: bool, _verbose: u8) -> Resul
     Ok(())
 }
 
+fn handle_explain(
+    args: &DebugArgs,
+    project_root: &Path,
+    config: &Config,
+    path: &Path,
+) -> Result<()> {
+    let relative_path: PathBuf = if path.is_absolute() {
+        path.strip_prefix(project_root)
+            .with_context(|| {
+                format!(
+                    "Path {} is outside the project root {}",
+                    path.display(),
+                    project_root.display()
+                )
+            })?
+            .to_path_buf()
+    } else {
+        path.to_path_buf()
+    };
+
+    let explanation = core::explain_path(project_root, config, &relative_path)
+        .context("Failed to evaluate filters for path")?;
+
+    if args.format_output.format.is_none() {
+        print_explanation_pretty(&explanation, config);
+    } else {
+        print_data_or_text(
+            &explanation,
+            None,
+            &args.format_output,
+            "json",
+            "PathExplanation",
+        )?;
+    }
+    Ok(())
+}
+
+fn print_explanation_pretty(explanation: &PathExplanation, config: &Config) {
+    let kind = if explanation.is_dir {
+        "directory"
+    } else {
+        "file"
+    };
+    println!(
+        "{} {} ({})",
+        "Explaining".green().bold(),
+        explanation.path.cyan(),
+        kind
+    );
+    if !explanation.exists {
+        println!("{}", "  note: path does not exist on disk".yellow());
+    }
+
+    let walker_step = if let Some(reason) = &explanation.skipped_internally {
+        reason.red()
+    } else if !config.general.use_gitignore {
+        "gitignore filtering disabled".dimmed()
+    } else if let Some(reason) = &explanation.gitignore {
+        reason.red()
+    } else {
+        "not ignored".green()
+    };
+    println!("  {:<10} {}", "walker:".bold(), walker_step);
+
+    print_decision_step("tree:", config.tree.enabled, Some(&explanation.tree));
+    print_decision_step("source:", config.source.enabled, Some(&explanation.source));
+    print_decision_step(
+        "docs:",
+        explanation.docs.is_some(),
+        explanation.docs.as_ref(),
+    );
+
+    let sections = if explanation.sections.is_empty() {
+        "none".red()
+    } else {
+        explanation.sections.join(", ").green()
+    };
+    println!("  {:<10} {}", "result:".bold(), sections);
+}
+
+fn print_decision_step(label: &str, enabled: bool, decision: Option<&FilterDecision>) {
+    let text = match decision {
+        _ if !enabled => "section disabled".dimmed(),
+        Some(d) if d.is_included() => d.describe().green(),
+        Some(d) => d.describe().red(),
+        None => "section disabled".dimmed(),
+    };
+    println!("  {:<10} {}", label.bold(), text);
+}
+
 fn get_relative_paths(files: &[FileInfo], project_root: &Path) -> Vec<String> {
     files
         .iter()
@@ -172,8 +280,9 @@ fn display_debug_rules(resolved_rules: &ResolvedRules) {
             "default" | "default+include" => origin_str.cyan(),
             "dynamic" => origin_str.magenta(),
             "include" => origin_str.green(),
-            "import" => origin_str.yellow(),
+            "import" | "import-ref" => origin_str.yellow(),
             "custom" => origin_str.blue(),
+            _ if origin_str.starts_with("inherited:") => origin_str.yellow(),
             _ => origin_str.dimmed(),
         };
         println!(
@@ -0,0 +1,238 @@
+use crate::cli_args::DoctorArgs;
+use crate::output::print_data_or_text;
+use anyhow::{Context, Result};
+use colored::*;
+use log;
+use serde::Serialize;
+use std::path::Path;
+use std::process::Command;
+use xcontext_core::{self as core, Config};
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Serialize)]
+struct DoctorCheck {
+    name: String,
+    status: CheckStatus,
+    detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<String>,
+}
+
+impl DoctorCheck {
+    fn new(name: &str, status: CheckStatus, detail: String, hint: Option<&str>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            detail,
+            hint: hint.map(String::from),
+        }
+    }
+}
+
+pub fn handle_doctor_command(args: DoctorArgs, _quiet: bool) -> Result<()> {
+    let project_root = Config::determine_project_root(args.project_config.project_root.as_ref())
+        .context("Failed to determine project root")?;
+    log::info!("Running doctor checks for: {}", project_root.display());
+
+    let mut checks = Vec::new();
+    let config = check_config(&args, &project_root, &mut checks);
+    checks.push(check_git(&project_root));
+    checks.push(check_save_dir(&config, &project_root));
+    checks.push(check_watch_delay(&config));
+    checks.extend(check_imports(&config, &project_root));
+
+    if args.format_output.format.is_none() {
+        print_checklist(&checks);
+    } else {
+        print_data_or_text(&checks, None, &args.format_output, "json", "DoctorChecks")?;
+    }
+
+    let failed = checks
+        .iter()
+        .filter(|c| c.status == CheckStatus::Fail)
+        .count();
+    if failed > 0 {
+        anyhow::bail!(core::AppError::Config(format!(
+            "{} doctor check(s) failed",
+            failed
+        )));
+    }
+    Ok(())
+}
+
+// Falls back to the defaults when the file cannot be loaded so the remaining checks still run
+fn check_config(args: &DoctorArgs, project_root: &Path, checks: &mut Vec<DoctorCheck>) -> Config {
+    let config_path = match Config::resolve_config_path(
+        project_root,
+        args.project_config.context_file.as_ref(),
+        args.project_config.disable_context_file,
+    ) {
+        Ok(path) => path,
+        Err(e) => {
+            checks.push(DoctorCheck::new(
+                "config",
+                CheckStatus::Fail,
+                e.to_string(),
+                Some("Check the --context-file value or run 'xcontext config --which'."),
+            ));
+            return Config::default();
+        }
+    };
+    let Some(path) = config_path else {
+        checks.push(DoctorCheck::new(
+            "config",
+            CheckStatus::Warn,
+            "No config file found; using defaults.".to_string(),
+            Some("Run 'xcontext config --save' to create one."),
+        ));
+        return Config::default();
+    };
+    match Config::load_from_path(&path) {
+        Ok(config) => {
+            checks.push(DoctorCheck::new(
+                "config",
+                CheckStatus::Pass,
+                format!("Parsed {}", path.display()),
+                None,
+            ));
+            config
+        }
+        Err(e) => {
+            checks.push(DoctorCheck::new(
+                "config",
+                CheckStatus::Fail,
+                e.to_string(),
+                Some("Fix the TOML error above; 'xcontext config' prints a valid template."),
+            ));
+            Config::default()
+        }
+    }
+}
+
+fn check_git(project_root: &Path) -> DoctorCheck {
+    let output = Command::new("git")
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .current_dir(project_root)
+        .output();
+    match output {
+        Ok(out) if out.status.success() => DoctorCheck::new(
+            "git",
+            CheckStatus::Pass,
+            "Project root is inside a git repository.".to_string(),
+            None,
+        ),
+        Ok(_) => DoctorCheck::new(
+            "git",
+            CheckStatus::Warn,
+            "Project root is not a git repository.".to_string(),
+            Some("Only needed for git-based options such as --exclude-untracked."),
+        ),
+        Err(e) => DoctorCheck::new(
+            "git",
+            CheckStatus::Warn,
+            format!("git could not be run: {}", e),
+            Some("Install git and make sure it is on PATH."),
+        ),
+    }
+}
+
+// The directory may not exist yet, so write access is probed on its nearest existing ancestor
+fn check_save_dir(config: &Config, project_root: &Path) -> DoctorCheck {
+    let save_dir = project_root.join(&config.save.output_dir);
+    let Some(existing) = save_dir.ancestors().find(|dir| dir.exists()) else {
+        return DoctorCheck::new(
+            "save_dir",
+            CheckStatus::Fail,
+            format!("No existing parent for {}", save_dir.display()),
+            Some("Set [save].output_dir to a valid path."),
+        );
+    };
+    match tempfile::tempfile_in(existing) {
+        Ok(_) => DoctorCheck::new(
+            "save_dir",
+            CheckStatus::Pass,
+            format!("{} is writable", existing.display()),
+            None,
+        ),
+        Err(e) => DoctorCheck::new(
+            "save_dir",
+            CheckStatus::Fail,
+            format!("Cannot write to {}: {}", existing.display(), e),
+            Some("Fix the directory permissions or change [save].output_dir."),
+        ),
+    }
+}
+
+fn check_watch_delay(config: &Config) -> DoctorCheck {
+    match config.get_watch_delay() {
+        Ok(delay) => DoctorCheck::new(
+            "watch_delay",
+            CheckStatus::Pass,
+            format!("'{}' ({:?})", config.watch.delay, delay),
+            None,
+        ),
+        Err(e) => DoctorCheck::new(
+            "watch_delay",
+            CheckStatus::Fail,
+            e.to_string(),
+            Some("Use a duration such as '500ms' or '2s' for [watch].delay."),
+        ),
+    }
+}
+
+fn check_imports(config: &Config, project_root: &Path) -> Vec<DoctorCheck> {
+    let imports = config
+        .rules
+        .import
+        .iter()
+        .map(|path| ("rules_import", path))
+        .chain(
+            config
+                .prompts
+                .import
+                .iter()
+                .map(|path| ("prompts_import", path)),
+        );
+    imports
+        .map(
+            |(name, path)| match core::config::resolve_import_path(project_root, path) {
+                Some(found) => DoctorCheck::new(
+                    name,
+                    CheckStatus::Pass,
+                    format!("{} resolves to {}", path.display(), found.display()),
+                    None,
+                ),
+                None => DoctorCheck::new(
+                    name,
+                    CheckStatus::Fail,
+                    format!("{} not found", path.display()),
+                    Some("Imports resolve relative to the project root, then .xtools/xcontext."),
+                ),
+            },
+        )
+        .collect()
+}
+
+fn print_checklist(checks: &[DoctorCheck]) {
+    println!();
+    println!("{}", " Doctor ".green().bold().underline());
+    for check in checks {
+        let marker = match check.status {
+            CheckStatus::Pass => "[pass]".green(),
+            CheckStatus::Warn => "[warn]".yellow(),
+            CheckStatus::Fail => "[fail]".red(),
+        };
+        println!("{} {:<15} {}", marker, check.name, check.detail);
+        if let Some(hint) = &check.hint {
+            println!("       {:<15} {}", "", hint.dimmed());
+        }
+    }
+    println!();
+}
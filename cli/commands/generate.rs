@@ -1,10 +1,13 @@
 use crate::cli_args::GenerateArgs; // Removed unused WatchArgs
+use crate::commands::metrics;
 use crate::load_config_for_command;
 use crate::output;
 use anyhow::{Context, Result};
 use colored::Colorize;
 use log;
+use std::collections::HashSet;
 use std::fs; // Added use std::fs
+use std::io::{self, IsTerminal};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use xcontext_core::{self as core, Config, ProjectContext}; // Use core types
@@ -31,6 +34,16 @@ pub fn handle_generate_command(args: GenerateArgs, quiet: bool, verbose: u8) ->
         chunks: &args.chunks,
         stdout: args.stdout,
         format_output: &args.format_output,
+        also_format: &args.also_format,
+        no_banner: args.no_banner,
+        max_tokens: args.max_tokens,
+        overview: args.overview,
+        manifest_only: args.manifest_only,
+        docs_only: args.docs_only,
+        with_metrics: args.with_metrics,
+        pretty_preview: args.pretty_preview,
+        sign: args.sign,
+        changed_paths: None,
     };
 
     // Use trigger_generation which handles the core logic + output
@@ -53,22 +66,78 @@ pub fn trigger_generation(
     );
 
     validate_args_for_generation(config, output_target_args)?;
+    // Confirmation banners are silenced by --no-banner without muting warnings
+    let banner_quiet = quiet || output_target_args.no_banner;
 
     log::debug!("Gathering files and tree elements...");
-    let (source_files, docs_files, tree_path_types) =
-        core::gather_files_and_tree(project_root, config, quiet)
-            .context("Failed to gather project files and directory structure")?;
+    let gathered = core::gather_files_and_tree(project_root, config, quiet)
+        .context("Failed to gather project files and directory structure")?;
+    let metrics_path = if output_target_args.with_metrics {
+        output_gathered_metrics(&gathered, config, output_target_args, project_root, quiet)?
+    } else {
+        None
+    };
+    let core::GatherResult {
+        source_files,
+        docs_files,
+        tree_path_types,
+    } = gathered;
     log::debug!(
         "Gathering complete. Found {} source, {} docs, {} tree elements.",
         source_files.len(),
         docs_files.len(),
         tree_path_types.len()
     );
+    // [rules.scoped] follows everything gathered, not what the token budget or --changed leave
+    let scope_paths: Vec<PathBuf> = source_files
+        .iter()
+        .chain(&docs_files)
+        .map(|f| f.path.clone())
+        .collect();
+
+    let source_files = match output_target_args.max_tokens {
+        Some(max_tokens) if config.source.enabled => {
+            apply_token_budget(source_files, max_tokens, config, project_root, quiet)?
+        }
+        _ => source_files,
+    };
+
+    let (source_files, docs_files) = match output_target_args.changed_paths {
+        Some(changed) => (
+            retain_changed_files(source_files, changed),
+            retain_changed_files(docs_files, changed),
+        ),
+        None => (source_files, docs_files),
+    };
+
+    if output_target_args.manifest_only {
+        return output_content_manifest(
+            &source_files,
+            &docs_files,
+            config,
+            output_target_args,
+            project_root,
+            banner_quiet,
+        );
+    }
+
+    if output_target_args.docs_only {
+        return output_docs_bundle(
+            &docs_files,
+            config,
+            output_target_args,
+            project_root,
+            banner_quiet,
+        );
+    }
 
     let tree_for_context: Option<Vec<core::TreeNode>> = if config.tree.enabled {
         log::debug!("Building tree structure...");
-        let tree = core::gather::build_tree_from_paths(&tree_path_types)
+        let mut tree = core::gather::build_tree_from_paths(&tree_path_types, &config.tree)
             .context("Failed to build directory tree structure")?;
+        if config.docs.directory_readmes {
+            core::gather::attach_directory_readmes(&mut tree, project_root);
+        }
         log::debug!("Tree structure built.");
         Some(tree)
     } else {
@@ -82,17 +151,34 @@ pub fn trigger_generation(
     log::debug!("Characteristics detected: {:?}", project_characteristics);
 
     log::debug!("Building initial project context (including rule resolution)...");
+    let gathered_paths: Vec<&Path> = scope_paths.iter().map(PathBuf::as_path).collect();
     let mut main_context = ProjectContext::build(
         project_root,
         config,
         tree_for_context,
         &project_characteristics,
+        &gathered_paths,
     )
     .context("Failed to build initial project context")?;
     log::debug!("Initial context built.");
 
+    // The overview replaces file contents with a metrics summary
+    let (source_files, docs_files) = if output_target_args.overview {
+        let all_files: Vec<core::FileInfo> = source_files.into_iter().chain(docs_files).collect();
+        main_context = main_context
+            .add_metrics_summary(&all_files, config)
+            .context("Failed to summarize metrics for overview")?;
+        (Vec::new(), Vec::new())
+    } else {
+        (source_files, docs_files)
+    };
+
     // Add docs if enabled
     main_context = main_context.add_docs(docs_files, project_root, config);
+    main_context = main_context.add_overview(&source_files, project_root, config);
+
+    let mut written_files = Vec::<PathBuf>::new(); // Listed in the checksum manifest
+    written_files.extend(metrics_path);
 
     // Handle source files (inline or chunking)
     if config.source.enabled {
@@ -103,9 +189,27 @@ pub fn trigger_generation(
             let (save_dir, filename_base, _) =
                 get_save_details_from_args(config, output_target_args.save.as_ref(), project_root);
 
-            let chunk_files_data =
-                core::chunking::split_files_into_chunks(source_files, chunk_size_str, project_root)
-                    .context("Failed to split files into chunks")?;
+            let (source_files, omitted_per_dir) =
+                ProjectContext::apply_max_files_per_dir(source_files, project_root, config);
+            let mut chunk_files_data = core::chunking::split_files_into_chunks(
+                source_files,
+                chunk_size_str,
+                project_root,
+                config.output.posix_paths,
+                config.output.include_file_hashes,
+                config.output.include_file_mode,
+            )
+            .context("Failed to split files into chunks")?;
+            if !config.source.annotations.is_empty() {
+                for chunk in &mut chunk_files_data {
+                    ProjectContext::apply_annotations(&mut chunk.files, &config.source);
+                }
+            }
+            if let Some(members) = &main_context.workspaces {
+                for chunk in &mut chunk_files_data {
+                    ProjectContext::tag_packages(&mut chunk.files, members);
+                }
+            }
 
             let mut chunk_file_paths = Vec::<PathBuf>::new();
             if !chunk_files_data.is_empty() {
@@ -118,21 +222,40 @@ pub fn trigger_generation(
                 })?;
             }
 
-            for (i, chunk_data) in chunk_files_data.iter().enumerate() {
-                let chunk_num = i + 1;
-                let chunk_filename = format!("{}_chunk_{}.json", filename_base, chunk_num); // Chunks always JSON
-                let chunk_path = save_dir.join(&chunk_filename);
-                // Use output::save_chunk_file, passing format_opts from output_target_args
-                output::save_chunk_file(
-                    chunk_data,
-                    &chunk_path,
-                    &output_target_args.format_output,
-                    quiet,
-                )?;
-                chunk_file_paths.push(chunk_path);
+            let chunk_format = output::explicit_format(output_target_args.format_output)
+                .unwrap_or(&config.output.format)
+                .to_lowercase();
+            if chunk_format == "yaml" && !chunk_files_data.is_empty() {
+                let stream_path = save_dir.join(format!("{}_chunks.yaml", filename_base));
+                output::save_chunk_stream(&chunk_files_data, &stream_path, config, banner_quiet)?;
+                chunk_file_paths.push(stream_path);
+            } else {
+                let total = chunk_files_data.len();
+                for (i, chunk_data) in chunk_files_data.iter().enumerate() {
+                    let chunk_num = i + 1;
+                    let chunk_filename =
+                        config.chunk_filename(&filename_base, chunk_num, total, "json")?;
+                    let chunk_path = save_dir.join(&chunk_filename);
+                    // Use output::save_chunk_file, passing format_opts from output_target_args
+                    output::save_chunk_file(
+                        chunk_data,
+                        &chunk_path,
+                        output_target_args.format_output,
+                        config.save.atomic_write,
+                        banner_quiet,
+                    )?;
+                    chunk_file_paths.push(chunk_path);
+                }
             }
 
-            main_context = main_context.add_chunk_paths(chunk_file_paths, &save_dir, config);
+            written_files.extend(chunk_file_paths.iter().cloned());
+            main_context = main_context.add_chunk_paths(
+                chunk_file_paths,
+                &chunk_files_data,
+                omitted_per_dir,
+                &save_dir,
+                config,
+            );
             log::info!("Chunking processing complete.");
 
             // Output the main context file (without sources, just chunk refs) if saving is requested
@@ -149,24 +272,38 @@ pub fn trigger_generation(
                     "Saving main context (with chunk references) to file: {}",
                     main_output_path.display()
                 );
-                output::print_context_or_save(
+                let written = output::print_context_or_save(
                     &main_context,
                     config,
                     Some(&main_output_path),
                     &output_target_args.format_output,
-                    quiet,
+                    banner_quiet,
                 )?;
+                if !banner_quiet {
+                    output::print_generation_summary(&main_context, &written);
+                }
+                written_files.push(main_output_path);
+                written_files.extend(save_additional_formats(
+                    &main_context,
+                    config,
+                    output_target_args,
+                    project_root,
+                    banner_quiet,
+                )?);
             } else if output_target_args.stdout {
                 // If stdout is forced even with chunking, print the main context (with chunk refs)
                 log::info!("Outputting main context (with chunk references) to stdout...");
-                output::print_context_or_save(
+                let written = output::print_context_or_save(
                     &main_context,
                     config,
                     None,
                     &output_target_args.format_output,
-                    quiet,
+                    banner_quiet,
                 )?;
-            } else if !quiet {
+                if !banner_quiet {
+                    output::print_generation_summary(&main_context, &written);
+                }
+            } else if !banner_quiet {
                 // Print confirmation about chunks being saved if not outputting main context elsewhere
                 println!(
                     "{} Source content chunked and saved in: {}",
@@ -178,13 +315,13 @@ pub fn trigger_generation(
             log::debug!("Adding source files inline...");
             main_context = main_context.add_files(source_files, project_root, config);
             // Output main context (with inline sources)
-            handle_final_output(
+            written_files.extend(handle_final_output(
                 &main_context,
                 config,
                 output_target_args,
                 project_root,
-                quiet,
-            )?;
+                banner_quiet,
+            )?);
         }
     } else {
         log::debug!("Source section disabled.");
@@ -196,12 +333,34 @@ pub fn trigger_generation(
             );
         }
         // Output main context (without any source section)
-        handle_final_output(
+        written_files.extend(handle_final_output(
             &main_context,
             config,
             output_target_args,
             project_root,
-            quiet,
+            banner_quiet,
+        )?);
+    }
+
+    if output_target_args.sign && output_target_args.save.is_some() {
+        let (save_dir, filename_base, extension) =
+            get_save_details_from_args(config, output_target_args.save.as_ref(), project_root);
+        let main_output_path = save_dir.join(format!("{}.{}", filename_base, extension));
+        written_files.push(output::write_provenance(
+            &main_output_path,
+            config.save.atomic_write,
+            banner_quiet,
+        )?);
+    }
+
+    if config.save.write_manifest && !written_files.is_empty() {
+        let (save_dir, _, _) =
+            get_save_details_from_args(config, output_target_args.save.as_ref(), project_root);
+        output::write_checksum_manifest(
+            &save_dir,
+            &written_files,
+            config.save.atomic_write,
+            banner_quiet,
         )?;
     }
 
@@ -215,6 +374,16 @@ pub struct OutputTargetArgs<'a> {
     pub chunks: &'a Option<String>,
     pub stdout: bool,
     pub format_output: &'a crate::cli_args::FormatOutputOpts,
+    pub also_format: &'a [String],
+    pub no_banner: bool,
+    pub max_tokens: Option<usize>,
+    pub overview: bool,       // Tree + metrics summary instead of file contents
+    pub manifest_only: bool,  // Paths, sizes and hashes only; nothing else is emitted
+    pub docs_only: bool,      // Markdown bundle of the docs section only
+    pub with_metrics: bool,   // Metrics from the same gathered files, beside the context
+    pub pretty_preview: bool, // Highlighted terminal rendering in place of stdout output
+    pub sign: bool,           // Write a provenance sidecar for the saved main context
+    pub changed_paths: Option<&'a HashSet<PathBuf>>, // Canonical paths; limits source/docs to these
 }
 
 // Helper to get save details from OutputTargetArgs
@@ -223,6 +392,21 @@ fn get_save_details_from_args(
     cli_save_opt: Option<&Option<PathBuf>>,
     project_root: &Path,
 ) -> (PathBuf, String, String) {
+    // A save path whose extension matches the output format names the file itself
+    if let Some(Some(cli_path)) = cli_save_opt {
+        let file_name = cli_path.file_stem().and_then(|s| s.to_str());
+        let extension = cli_path.extension().and_then(|e| e.to_str());
+        // Compared by extension so aliases ('md', 'yml') match their canonical format
+        let matches_format = output::format_from_path(cli_path).map(extension_for_format)
+            == Some(extension_for_format(&config.output.format));
+        if let (Some(file_name), Some(extension), true) = (file_name, extension, matches_format) {
+            let parent = cli_path.parent().unwrap_or_else(|| Path::new(""));
+            let save_dir = project_root.join(parent); // join keeps absolute parents as-is
+            log::trace!("Save path treated as file: {}", cli_path.display());
+            return (save_dir, file_name.to_string(), extension.to_string());
+        }
+    }
+
     let save_dir_base = match cli_save_opt {
         Some(Some(cli_path)) => {
             log::trace!(
@@ -267,25 +451,199 @@ fn get_save_details_from_args(
     let filename_base = filename_base_str.to_string();
     log::trace!("Using filename base: {}", filename_base);
 
-    let extension = config.save.extension.as_deref().unwrap_or_else(|| {
-        match config.output.format.to_lowercase().as_str() {
-            "yaml" | "yml" => "yaml",
-            "xml" => "xml",
-            _ => "json",
-        }
-    });
+    let extension = config
+        .save
+        .extension
+        .as_deref()
+        .unwrap_or_else(|| extension_for_format(&config.output.format));
     log::trace!("Using save extension: {}", extension);
 
     (save_dir, filename_base, extension.to_string())
 }
 
+fn extension_for_format(format: &str) -> &'static str {
+    match format.to_lowercase().as_str() {
+        "yaml" | "yml" => "yaml",
+        "xml" => "xml",
+        "doc-tags" => "txt",
+        "markdown" | "md" => "md",
+        _ => "json",
+    }
+}
+
+// Emits the path/size/hash fingerprint instead of a context, in the configured format
+fn output_content_manifest(
+    source_files: &[core::FileInfo],
+    docs_files: &[core::FileInfo],
+    config: &Config,
+    output_target_args: &OutputTargetArgs,
+    project_root: &Path,
+    quiet: bool,
+) -> Result<()> {
+    let manifest = core::ContentManifest::from_files(
+        source_files,
+        docs_files,
+        project_root,
+        config.output.posix_paths,
+    );
+    log::info!(
+        "Built content manifest for {} files (digest {}).",
+        manifest.files.len(),
+        manifest.digest
+    );
+
+    // doc-tags and markdown have no structured form, so they fall back to JSON
+    let format = match output::explicit_format(output_target_args.format_output)
+        .unwrap_or(&config.output.format)
+        .to_lowercase()
+        .as_str()
+    {
+        "yaml" | "yml" => "yaml",
+        "xml" => "xml",
+        _ => "json",
+    };
+    let format_opts = crate::cli_args::FormatOutputOpts {
+        format: Some(format.to_string()),
+        ..output_target_args.format_output.clone()
+    };
+
+    if output_target_args.save.is_some() && !output_target_args.stdout {
+        let (save_dir, filename_base, _) =
+            get_save_details_from_args(config, output_target_args.save.as_ref(), project_root);
+        fs::create_dir_all(&save_dir)
+            .with_context(|| format!("Failed to create output directory {}", save_dir.display()))?;
+        let path = save_dir.join(format!("{}.manifest.{}", filename_base, format));
+        output::save_data_to_file(&manifest, &path, &format_opts, "ContentManifest", quiet)
+    } else {
+        output::print_data_or_text(&manifest, None, &format_opts, format, "ContentManifest")
+    }
+}
+
+// Metrics over everything gathered (before token budgets or --changed narrow it down); saved as
+// JSON next to the context, or summarized on stderr so stdout keeps only the context
+fn output_gathered_metrics(
+    gathered: &core::GatherResult,
+    config: &Config,
+    output_target_args: &OutputTargetArgs,
+    project_root: &Path,
+    quiet: bool,
+) -> Result<Option<PathBuf>> {
+    let files: Vec<&core::FileInfo> = gathered.all_files().collect();
+    let approximate = !core::tokens::tokenizer_available();
+    let metrics = metrics::calculate_metrics(&files, project_root, approximate)
+        .context("Failed to calculate metrics from gathered files")?;
+
+    if output_target_args.save.is_some() && !output_target_args.stdout {
+        let (save_dir, filename_base, _) =
+            get_save_details_from_args(config, output_target_args.save.as_ref(), project_root);
+        let path = save_dir.join(format!("{}.metrics.json", filename_base));
+        let content = core::output_formats::serialize_context_to_json(&metrics, true)?;
+        output::print_or_save_text(&content, Some(&path), config.save.atomic_write, quiet)?;
+        return Ok(Some(path));
+    }
+    if !quiet {
+        let approx_note = if approximate { " (approximate)" } else { "" };
+        eprintln!(
+            "{} {} files, {} lines, {}, ~{} tokens{}",
+            "Metrics:".dimmed(),
+            metrics.total_files,
+            metrics.total_lines,
+            metrics.total_bytes_readable,
+            metrics.estimated_tokens,
+            approx_note
+        );
+    }
+    Ok(None)
+}
+
+// Emits the docs as a single Markdown document instead of a context
+fn output_docs_bundle(
+    docs_files: &[core::FileInfo],
+    config: &Config,
+    output_target_args: &OutputTargetArgs,
+    project_root: &Path,
+    quiet: bool,
+) -> Result<()> {
+    if docs_files.is_empty() {
+        log::warn!("No docs files were gathered; check [docs].enabled and its include patterns.");
+    }
+    // docs.max_files applies as it does to the docs section: the first N in path order
+    let docs_files = match config.docs.max_files {
+        Some(max_files) if docs_files.len() > max_files => {
+            log::info!(
+                "Omitted {} docs files from the bundle (max_files = {})",
+                docs_files.len() - max_files,
+                max_files
+            );
+            &docs_files[..max_files]
+        }
+        _ => docs_files,
+    };
+    let title = config.get_effective_project_name(project_root);
+    let bundle =
+        core::serialize_docs_bundle(&title, docs_files, project_root, config.output.posix_paths);
+    log::info!("Built docs bundle from {} files.", docs_files.len());
+
+    let output_path = if output_target_args.save.is_some() && !output_target_args.stdout {
+        let (save_dir, filename_base, _) =
+            get_save_details_from_args(config, output_target_args.save.as_ref(), project_root);
+        fs::create_dir_all(&save_dir)
+            .with_context(|| format!("Failed to create output directory {}", save_dir.display()))?;
+        Some(save_dir.join(format!("{}.docs.md", filename_base)))
+    } else {
+        None
+    };
+    output::print_or_save_text(
+        &bundle,
+        output_path.as_deref(),
+        config.save.atomic_write,
+        quiet,
+    )
+}
+
+// Writes the main context once more for every `--also-format` entry, next to the primary file
+fn save_additional_formats(
+    main_context: &ProjectContext,
+    config: &Config,
+    output_target_args: &OutputTargetArgs,
+    project_root: &Path,
+    quiet: bool,
+) -> Result<Vec<PathBuf>> {
+    let mut saved_paths = Vec::new();
+    if output_target_args.also_format.is_empty() || output_target_args.save.is_none() {
+        return Ok(saved_paths);
+    }
+    let (save_dir, filename_base, primary_extension) =
+        get_save_details_from_args(config, output_target_args.save.as_ref(), project_root);
+
+    for format in output_target_args.also_format {
+        let extension = extension_for_format(format);
+        if extension == primary_extension {
+            log::debug!(
+                "Skipping additional format '{}': same extension as the primary output.",
+                format
+            );
+            continue;
+        }
+        let output_path = save_dir.join(format!("{}.{}", filename_base, extension));
+        log::info!(
+            "Saving additional '{}' output to file: {}",
+            format,
+            output_path.display()
+        );
+        output::save_context_in_format(main_context, config, &output_path, format, quiet)?;
+        saved_paths.push(output_path);
+    }
+    Ok(saved_paths)
+}
+
 fn handle_final_output(
     main_context: &ProjectContext,
     config: &Config,
     output_target_args: &OutputTargetArgs,
     project_root: &Path,
     quiet: bool,
-) -> Result<()> {
+) -> Result<Vec<PathBuf>> {
     log::debug!("Determining final output target...");
     let mut output_target_path: Option<PathBuf> = None;
     let needs_saving_to_disk = output_target_args.save.is_some();
@@ -305,13 +663,81 @@ fn handle_final_output(
         log::debug!("Output target set to stdout (default).");
     }
 
-    output::print_context_or_save(
+    if output_target_args.pretty_preview
+        && output_target_path.is_none()
+        && io::stdout().is_terminal()
+    {
+        crate::preview::print_pretty_preview(main_context)?;
+        return Ok(Vec::new());
+    }
+
+    let written = output::print_context_or_save(
         main_context,
         config,
         output_target_path.as_deref(),
         &output_target_args.format_output,
         quiet,
+    )?;
+    if !quiet {
+        output::print_generation_summary(main_context, &written);
+    }
+    let mut saved_paths: Vec<PathBuf> = output_target_path.into_iter().collect();
+    saved_paths.extend(save_additional_formats(
+        main_context,
+        config,
+        output_target_args,
+        project_root,
+        quiet,
+    )?);
+    Ok(saved_paths)
+}
+
+fn retain_changed_files(
+    files: Vec<core::FileInfo>,
+    changed: &HashSet<PathBuf>,
+) -> Vec<core::FileInfo> {
+    files
+        .into_iter()
+        .filter(|f| {
+            let canonical = f.path.canonicalize().unwrap_or_else(|_| f.path.clone());
+            changed.contains(&canonical)
+        })
+        .collect()
+}
+
+fn apply_token_budget(
+    source_files: Vec<core::FileInfo>,
+    max_tokens: usize,
+    config: &Config,
+    project_root: &Path,
+    quiet: bool,
+) -> Result<Vec<core::FileInfo>> {
+    let outcome = core::budget::enforce_token_budget(
+        source_files,
+        max_tokens,
+        &config.source.priority,
+        project_root,
     )
+    .context("Failed to enforce token budget")?;
+
+    if !outcome.dropped.is_empty() {
+        crate::record_warning();
+    }
+    if !quiet && !outcome.dropped.is_empty() {
+        eprintln!(
+            "{} Token budget {}: kept {} source files ({} tokens), dropped {}:",
+            "⚠️".yellow(),
+            max_tokens,
+            outcome.kept.len(),
+            outcome.used_tokens,
+            outcome.dropped.len()
+        );
+        for path in &outcome.dropped {
+            let relative = path.strip_prefix(project_root).unwrap_or(path);
+            eprintln!("  - {}", relative.display().to_string().dimmed());
+        }
+    }
+    Ok(outcome.kept)
 }
 
 fn validate_args_for_generation(config: &Config, args: &OutputTargetArgs) -> Result<()> {
@@ -321,16 +747,14 @@ fn validate_args_for_generation(config: &Config, args: &OutputTargetArgs) -> Res
                 "Chunking (-c) cannot be used when source file inclusion ([source].enabled=false) is disabled".to_string()
             ));
         }
-        let format = args
-            .format_output
-            .format
-            .as_deref()
-            .unwrap_or(&config.output.format);
-        if format.to_lowercase() != "json" {
+        let format = output::explicit_format(args.format_output).unwrap_or(&config.output.format);
+        if !matches!(format.to_lowercase().as_str(), "json" | "yaml") {
             anyhow::bail!(core::AppError::Chunking(
-                "Chunking requires the output format to be 'json'. Use '-f json'.".to_string()
+                "Chunking requires the output format to be 'json' or 'yaml'. Use '-f json' or '-f yaml'.".to_string()
             ));
         }
+        // Rejects a bad save.chunk_filename_template before anything is written
+        config.chunk_filename("base", 1, 1, "json")?;
         // Chunking implies saving, so stdout without save doesn't make sense unless explicitly handled
         if args.stdout && args.save.is_none() {
             anyhow::bail!(core::AppError::InvalidArgument(
@@ -0,0 +1,64 @@
+use crate::cli_args::{Cli, InfoArgs};
+use crate::output::print_data_or_text;
+use anyhow::Result;
+use clap::CommandFactory;
+use serde::Serialize;
+
+const OUTPUT_FORMATS: &[&str] = &["json", "yaml", "xml", "doc-tags", "markdown"];
+
+// Capabilities wrappers can probe for; add an entry here whenever a user-facing feature lands
+const CAPABILITIES: &[&str] = &[
+    "chunks",
+    "chunk-stream",
+    "overview",
+    "manifest-only",
+    "pretty-preview",
+    "max-tokens",
+    "also-format",
+    "format-fallback",
+    "sign",
+    "profiles",
+    "set-overrides",
+    "rule-include-exclude",
+    "watch-poll",
+    "watch-delta",
+    "watch-initial-delay",
+    "metrics-cost",
+    "metrics-by-dir",
+    "metrics-include-all",
+    "show-summary",
+    "output-sections",
+    "quick-roots",
+    "docs-only",
+    "truncate-lines-over",
+    "strict",
+    "xml-root-namespace",
+    "with-metrics",
+    "max-tokens-per-file",
+    "chunk-filename-template",
+];
+
+#[derive(Serialize)]
+struct Capabilities {
+    name: &'static str,
+    version: &'static str,
+    output_formats: &'static [&'static str],
+    commands: Vec<String>,
+    capabilities: &'static [&'static str],
+    compiled_features: &'static [&'static str],
+}
+
+pub fn handle_info_command(args: &InfoArgs) -> Result<()> {
+    let info = Capabilities {
+        name: env!("CARGO_BIN_NAME"),
+        version: env!("CARGO_PKG_VERSION"),
+        output_formats: OUTPUT_FORMATS,
+        commands: Cli::command()
+            .get_subcommands()
+            .map(|c| c.get_name().to_string())
+            .collect(),
+        capabilities: CAPABILITIES,
+        compiled_features: xcontext_core::COMPILED_FEATURES,
+    };
+    print_data_or_text(&info, None, &args.format_output, "json", "Capabilities")
+}
@@ -0,0 +1,77 @@
+use crate::cli_args::MergeArgs;
+use crate::output::{print_data_or_text, save_data_to_file};
+use anyhow::{Context, Result};
+use log;
+use std::fs;
+use std::path::{Path, PathBuf};
+use xcontext_core::{ChunkFile, SourceRepresentation, chunking};
+
+pub fn handle_merge_command(args: &MergeArgs, quiet: bool) -> Result<()> {
+    let chunk_paths = find_chunk_files(&args.dir)?;
+    let mut chunks = Vec::with_capacity(chunk_paths.len());
+    for path in &chunk_paths {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read chunk file {}", path.display()))?;
+        if is_yaml_stream(path) {
+            chunks.extend(
+                chunking::parse_yaml_chunk_stream(&content)
+                    .with_context(|| format!("Failed to parse chunk file {}", path.display()))?,
+            );
+        } else if let Some(chunk) = parse_json_chunk(&content)
+            .with_context(|| format!("Failed to parse chunk file {}", path.display()))?
+        {
+            chunks.push(chunk);
+        } else {
+            log::debug!("Skipping {}: not a chunk file", path.display());
+        }
+    }
+
+    log::info!("Found {} chunks in {}", chunks.len(), args.dir.display());
+
+    let files = chunking::merge_chunks(chunks)
+        .with_context(|| format!("Failed to merge chunks in {}", args.dir.display()))?;
+    let merged = SourceRepresentation {
+        files: Some(files),
+        ..Default::default()
+    };
+
+    match &args.output {
+        Some(path) => save_data_to_file(&merged, path, &args.format_output, "Source", quiet),
+        None => print_data_or_text(&merged, None, &args.format_output, "json", "Source"),
+    }
+}
+
+// Candidate chunk files: every '*.json' (names follow save.chunk_filename_template, so they are
+// told apart by content) plus '<base>_chunks.yaml' streams
+fn find_chunk_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries =
+        fs::read_dir(dir).with_context(|| format!("Failed to read directory {}", dir.display()))?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.is_file()
+                && (path.extension().is_some_and(|ext| ext == "json") || is_yaml_stream(path))
+        })
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+// A JSON file is a chunk when it carries a top-level `chunkInfo`; other JSON (e.g. the
+// '.metrics.json' or manifest saved alongside) yields None
+fn parse_json_chunk(content: &str) -> Result<Option<ChunkFile>> {
+    let value: serde_json::Value = match serde_json::from_str(content) {
+        Ok(value) => value,
+        Err(_) => return Ok(None),
+    };
+    if value.get("chunkInfo").is_none() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_value(value)?))
+}
+
+fn is_yaml_stream(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|name| name.ends_with("_chunks.yaml"))
+}
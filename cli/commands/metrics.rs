@@ -6,8 +6,8 @@ use byte_unit::{Byte, UnitType};
 use log;
 use pathdiff; // Added use
 use serde::Serialize;
+use std::collections::BTreeMap;
 use std::path::Path;
-use tiktoken_rs::cl100k_base;
 use xcontext_core::{self as core, Config, FileInfo}; // Use core types
 
 #[derive(Debug, Serialize)]
@@ -17,9 +17,32 @@ pub struct ProjectMetrics {
     pub total_bytes: u128,
     pub total_bytes_readable: String,
     pub estimated_tokens: usize,
+    pub estimated: &'static str, // "precise" (cl100k_base) or "approximate" (bytes/4)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_cost: Option<CostEstimate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_directory: Option<Vec<DirMetrics>>,
     pub files_details: Vec<FileMetrics>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct DirMetrics {
+    pub directory: String, // "." for files at the project root
+    pub files: usize,
+    pub lines: usize,
+    pub bytes: usize,
+    pub bytes_readable: String,
+    pub estimated_tokens: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CostEstimate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub price_per_1k_tokens: f64,
+    pub estimated_cost_usd: f64,
+}
+
 #[derive(Debug, Serialize)]
 pub struct FileMetrics {
     pub path: String,
@@ -29,12 +52,35 @@ pub struct FileMetrics {
     pub estimated_tokens: usize,
 }
 
+// Drops the filters that decide what goes into a context so metrics see the whole repository;
+// ignore files and built-in ignores are left alone
+fn measure_everything(config: &mut Config) {
+    // Anything that is not a doc falls through to source, so opening source up covers docs too
+    config.source.include = Some(Vec::new());
+    config.source.exclude = Some(Vec::new());
+    config.source.enabled = true;
+    config.source.max_lines = None;
+    config.source.max_content_bytes = None;
+    config.source.truncate_lines_over = None;
+    config.source.max_tokens_per_file = None;
+    config.source.max_age = None;
+    config.source.redact_env_values = false;
+    config.source.transforms.clear();
+    config.common_filters.exclude_generated = false;
+}
+
 pub fn handle_metrics_command(args: MetricsArgs, quiet: bool) -> Result<()> {
+    let price_per_1k = if args.cost {
+        Some(resolve_price_per_1k(&args)?)
+    } else {
+        None
+    };
+
     let project_root = Config::determine_project_root(args.project_config.project_root.as_ref())
         .context("Failed to determine project root")?;
     log::info!("Project root determined: {}", project_root.display());
 
-    let config = load_config_for_command(
+    let mut config = load_config_for_command(
         &project_root,
         &args.project_config,
         None,
@@ -42,21 +88,37 @@ pub fn handle_metrics_command(args: MetricsArgs, quiet: bool) -> Result<()> {
         Some(&args.format_output), // Pass format override options
     )
     .context("Failed to load configuration for metrics command")?;
+    if args.include_all {
+        measure_everything(&mut config);
+    }
 
     log::debug!("Gathering files for metrics...");
-    let (source_files, docs_files, _) = core::gather_files_and_tree(&project_root, &config, quiet)
+    let gathered = core::gather_files_and_tree(&project_root, &config, quiet)
         .context("Failed to gather files for metrics calculation")?;
     log::debug!("Files gathered.");
 
-    let combined_files: Vec<&FileInfo> = source_files.iter().chain(docs_files.iter()).collect();
+    let combined_files: Vec<&FileInfo> = gathered.all_files().collect();
 
     if combined_files.is_empty() && !quiet {
         println!("No source or documentation files found to calculate metrics.");
         return Ok(()); // Exit gracefully if no files
     }
 
+    let approximate = args.approx_tokens || !core::tokens::tokenizer_available();
+    if approximate && !args.approx_tokens {
+        log::warn!("cl100k_base tokenizer unavailable; token counts are approximate (bytes/4).");
+    }
+
     log::debug!("Calculating metrics...");
-    let metrics = calculate_metrics(&combined_files, &project_root)?;
+    let mut metrics = calculate_metrics(&combined_files, &project_root, approximate)?;
+    metrics.estimated_cost = price_per_1k.map(|price| CostEstimate {
+        model: args.model.clone(),
+        price_per_1k_tokens: price,
+        estimated_cost_usd: metrics.estimated_tokens as f64 / 1000.0 * price,
+    });
+    if let Some(depth) = args.by_dir {
+        metrics.by_directory = Some(aggregate_by_dir(&metrics.files_details, depth.max(1)));
+    }
     log::debug!("Metrics calculation complete.");
 
     if args.format_output.format.is_none() {
@@ -73,9 +135,75 @@ pub fn handle_metrics_command(args: MetricsArgs, quiet: bool) -> Result<()> {
     }
 }
 
-fn calculate_metrics(files: &[&FileInfo], project_root: &Path) -> Result<ProjectMetrics> {
-    let bpe =
-        cl100k_base().map_err(|e| anyhow::anyhow!(core::AppError::TikToken(e.to_string())))?;
+fn resolve_price_per_1k(args: &MetricsArgs) -> Result<f64> {
+    let price = match (args.price_per_1k, args.model.as_deref()) {
+        (Some(price), _) => price,
+        (None, Some(model)) => core::tokens::model_input_price_per_1k(model).ok_or_else(|| {
+            core::AppError::InvalidArgument(format!(
+                "Unknown model '{}' for --cost. Known models: {}. Use --price-per-1k instead.",
+                model,
+                core::tokens::priced_models().join(", ")
+            ))
+        })?,
+        (None, None) => anyhow::bail!(core::AppError::InvalidArgument(
+            "--cost requires --price-per-1k <USD> or --model <NAME>".to_string()
+        )),
+    };
+    if !price.is_finite() || price < 0.0 {
+        anyhow::bail!(core::AppError::InvalidArgument(format!(
+            "Invalid --price-per-1k value: {}",
+            price
+        )));
+    }
+    Ok(price)
+}
+
+// Groups files on their first `depth` directory components, largest token count first
+fn aggregate_by_dir(files: &[FileMetrics], depth: usize) -> Vec<DirMetrics> {
+    let mut groups: BTreeMap<String, DirMetrics> = BTreeMap::new();
+    for file in files {
+        let parent = Path::new(&file.path).parent().unwrap_or(Path::new(""));
+        let components: Vec<String> = parent
+            .components()
+            .take(depth)
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        let directory = if components.is_empty() {
+            ".".to_string()
+        } else {
+            components.join("/")
+        };
+        let entry = groups
+            .entry(directory.clone())
+            .or_insert_with(|| DirMetrics {
+                directory,
+                files: 0,
+                lines: 0,
+                bytes: 0,
+                bytes_readable: String::new(),
+                estimated_tokens: 0,
+            });
+        entry.files += 1;
+        entry.lines += file.lines;
+        entry.bytes += file.bytes;
+        entry.estimated_tokens += file.estimated_tokens;
+    }
+    let mut dirs: Vec<DirMetrics> = groups.into_values().collect();
+    for dir in &mut dirs {
+        dir.bytes_readable = Byte::from_u128(dir.bytes as u128)
+            .unwrap_or_default()
+            .get_appropriate_unit(UnitType::Binary)
+            .to_string();
+    }
+    dirs.sort_by_key(|dir| std::cmp::Reverse(dir.estimated_tokens));
+    dirs
+}
+
+pub fn calculate_metrics(
+    files: &[&FileInfo],
+    project_root: &Path,
+    approximate: bool,
+) -> Result<ProjectMetrics> {
     let mut total_files = 0;
     let mut total_lines = 0;
     let mut total_bytes: u128 = 0;
@@ -90,7 +218,11 @@ fn calculate_metrics(files: &[&FileInfo], project_root: &Path) -> Result<Project
         let lines = file_info.content.lines().count();
         let bytes = file_info.size;
         // Estimate tokens in parallel? Might be overkill unless content is huge
-        let tokens = bpe.encode_ordinary(&file_info.content).len();
+        let tokens = if approximate {
+            core::tokens::approximate_tokens(&file_info.content)
+        } else {
+            core::tokens::estimate_tokens(&file_info.content)?
+        };
 
         let relative_path = pathdiff::diff_paths(&file_info.path, project_root) // Added use pathdiff
             .unwrap_or_else(|| file_info.path.clone())
@@ -128,6 +260,13 @@ fn calculate_metrics(files: &[&FileInfo], project_root: &Path) -> Result<Project
         total_bytes,
         total_bytes_readable: total_size_readable,
         estimated_tokens: total_tokens,
+        estimated: if approximate {
+            "approximate"
+        } else {
+            "precise"
+        },
+        estimated_cost: None, // Filled in by the caller when --cost is given
+        by_directory: None,   // Filled in by the caller when --by-dir is given
         files_details,
     })
 }
@@ -11,15 +11,27 @@ use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use serde::Serialize;
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf; // Removed unused Path import
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use std::sync::mpsc;
+use std::thread;
 use xcontext_core::Config;
+use xcontext_core::output_formats::relative_output_path;
 
 #[derive(Debug, Serialize)]
 struct QuickOutput {
     files: HashMap<String, String>,
 }
 
+#[derive(Debug, Serialize)]
+struct QuickStreamEntry<'a> {
+    path: &'a str,
+    content: &'a str,
+}
+
+// Files read in parallel per window; also bounds how many are held in memory at once
+const STREAM_WINDOW: usize = 64;
+
 pub fn handle_quick_command(args: QuickArgs, quiet: bool, verbose: u8) -> Result<()> {
     let project_root = Config::determine_project_root(args.project_config.project_root.as_ref())
         .context("Failed to determine project root")?;
@@ -35,33 +47,186 @@ pub fn handle_quick_command(args: QuickArgs, quiet: bool, verbose: u8) -> Result
     )
     .context("Failed to load configuration for quick command")?;
 
-    let mut pattern_to_use = args.pattern.clone();
-    let potential_path = project_root.join(&args.pattern);
+    let roots = search_roots(&args.root, &project_root, config.output.posix_paths)?;
+    let mut paths_to_read = Vec::new();
+    for root in &roots {
+        for path in find_matches(&args.pattern, &root.path, &config, quiet, verbose)? {
+            let key = output_key(&path, root, args.absolute_paths, config.output.posix_paths);
+            paths_to_read.push((path, key));
+        }
+    }
+    if args.count {
+        print_match_counts(&paths_to_read);
+        return Ok(());
+    }
+    if args.stream {
+        return stream_matched_files(paths_to_read, &args.stream_format, quiet);
+    }
+    log::info!(
+        "Found {} files matching pattern. Reading content...",
+        paths_to_read.len()
+    );
+
+    let results: Vec<Result<(String, String)>> = paths_to_read
+        .par_iter()
+        .map(|(path, key)| {
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read file {}", path.display()))?;
+            Ok((key.clone(), content))
+        })
+        .collect();
+
+    let mut files_map = HashMap::new();
+    let mut read_errors = Vec::new();
+
+    for result in results {
+        match result {
+            Ok((path_str, content)) => {
+                files_map.insert(path_str, content);
+            }
+            Err(e) => {
+                read_errors.push(format!("{:#}", e));
+            }
+        }
+    }
+
+    if !read_errors.is_empty() {
+        crate::record_warning();
+    }
+    if !read_errors.is_empty() && !quiet {
+        eprintln!(
+            "{}",
+            "Warning: Errors encountered during file reading:".yellow()
+        );
+        for err_msg in read_errors {
+            eprintln!(" - {}", err_msg);
+        }
+        eprintln!("---");
+    }
+
+    if files_map.is_empty() && !quiet {
+        println!("No files matched the pattern '{}'.", args.pattern);
+        return Ok(());
+    }
+
+    let output_data = QuickOutput { files: files_map };
+
+    let default_format = "json";
+    print_data_or_text(
+        &output_data,
+        None,
+        &args.format_output,
+        default_format,
+        "QuickOutput",
+    )
+}
+
+// A directory searched for matches; `prefix` (the directory's path below the roots' common
+// ancestor) keeps keys apart when several roots are searched
+struct SearchRoot {
+    path: PathBuf,
+    prefix: Option<String>,
+}
+
+fn search_roots(
+    cli_roots: &[PathBuf],
+    project_root: &Path,
+    posix_paths: bool,
+) -> Result<Vec<SearchRoot>> {
+    if cli_roots.is_empty() {
+        return Ok(vec![SearchRoot {
+            path: project_root.to_path_buf(),
+            prefix: None,
+        }]);
+    }
+    let paths = cli_roots
+        .iter()
+        .map(|root| {
+            root.canonicalize()
+                .with_context(|| format!("Quick search root not found: {}", root.display()))
+        })
+        .collect::<Result<Vec<PathBuf>>>()?;
+    if paths.len() == 1 {
+        return Ok(paths
+            .into_iter()
+            .map(|path| SearchRoot { path, prefix: None })
+            .collect());
+    }
+
+    let mut ancestor = paths[0].clone();
+    for path in &paths[1..] {
+        while !path.starts_with(&ancestor) && ancestor.pop() {}
+    }
+    let mut roots: Vec<SearchRoot> = Vec::with_capacity(paths.len());
+    for path in paths {
+        let mut prefix = relative_output_path(&path, &ancestor, posix_paths);
+        if prefix.is_empty() {
+            // The root is the common ancestor itself, so it goes by its own name
+            prefix = path.file_name().map_or_else(
+                || path.display().to_string(),
+                |n| n.to_string_lossy().into_owned(),
+            );
+        }
+        if let Some(existing) = roots.iter().find(|r| r.prefix.as_deref() == Some(&prefix)) {
+            anyhow::bail!(
+                "Quick search roots {} and {} would both be keyed as '{}'",
+                existing.path.display(),
+                path.display(),
+                prefix
+            );
+        }
+        roots.push(SearchRoot {
+            path,
+            prefix: Some(prefix),
+        });
+    }
+    Ok(roots)
+}
+
+fn output_key(path: &Path, root: &SearchRoot, absolute: bool, posix_paths: bool) -> String {
+    if absolute {
+        let absolute_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        return relative_output_path(&absolute_path, Path::new(""), posix_paths);
+    }
+    let relative = relative_output_path(path, &root.path, posix_paths);
+    match &root.prefix {
+        Some(prefix) => format!("{}/{}", prefix, relative),
+        None => relative,
+    }
+}
+
+// Walks `root` (respecting ignore files) for files whose root-relative path matches `pattern`;
+// a pattern naming a directory under the root matches everything below it
+fn find_matches(
+    pattern: &str,
+    root: &Path,
+    config: &Config,
+    quiet: bool,
+    verbose: u8,
+) -> Result<Vec<PathBuf>> {
+    let mut pattern_to_use = pattern.to_string();
+    let potential_path = root.join(pattern);
     let mut info_msg = None;
 
     // Check if the pattern looks like a directory and adjust glob
     if potential_path.is_dir() {
-        pattern_to_use = format!(
-            "{}**/*",
-            args.pattern.trim_end_matches(&['/', '\\'] as &[char])
-        );
+        pattern_to_use = format!("{}**/*", pattern.trim_end_matches(&['/', '\\'] as &[char]));
         info_msg = Some(format!(
             "{} Interpreting directory input '{}' as glob '{}'",
             "ℹ️".blue(),
-            args.pattern,
+            pattern,
             pattern_to_use
         ));
-    } else if args.pattern.ends_with(&['/', '\\'] as &[char]) {
+    } else if pattern.ends_with(&['/', '\\'] as &[char]) {
         // If it ends with slash but isn't a dir, warn and use modified pattern
         if !quiet {
             eprintln!(
                 "{} Directory pattern '{}' matches no existing directory, using pattern without trailing slash.",
                 "⚠️".yellow(),
-                args.pattern
+                pattern
             );
         }
-        pattern_to_use = args
-            .pattern
+        pattern_to_use = pattern
             .trim_end_matches(&['/', '\\'] as &[char])
             .to_string();
     }
@@ -75,14 +240,14 @@ pub fn handle_quick_command(args: QuickArgs, quiet: bool, verbose: u8) -> Result
     let glob_pattern = Pattern::new(&pattern_to_use).with_context(|| {
         format!(
             "Invalid glob pattern for quick: '{}' (processed as '{}')",
-            args.pattern, pattern_to_use
+            pattern, pattern_to_use
         )
     })?;
 
     let use_gitignore = config.general.use_gitignore;
     let _enable_builtin_ignore = config.general.enable_builtin_ignore; // TODO: Apply built-in ignores too?
 
-    let mut builder = WalkBuilder::new(&project_root);
+    let mut builder = WalkBuilder::new(root);
     builder.hidden(false); // Include hidden files unless filtered by ignores
     builder.ignore(use_gitignore);
     builder.git_ignore(use_gitignore);
@@ -93,10 +258,11 @@ pub fn handle_quick_command(args: QuickArgs, quiet: bool, verbose: u8) -> Result
     let walker = builder.build_parallel();
     let (tx_path, rx_path) = mpsc::channel::<PathBuf>();
     let glob_pattern_outer_clone = glob_pattern.clone(); // Clone for closure
-    let proj_root_clone = project_root.clone(); // Clone for closure
+    let proj_root_clone = root.to_path_buf(); // Clone for closure
 
     log::debug!(
-        "Starting parallel walk for pattern: {}",
+        "Starting parallel walk of {} for pattern: {}",
+        root.display(),
         glob_pattern.as_str()
     );
     walker.run(move || {
@@ -125,66 +291,97 @@ pub fn handle_quick_command(args: QuickArgs, quiet: bool, verbose: u8) -> Result
             WalkState::Continue
         })
     }); // The original tx_path (owned by the closure) goes out of scope here
+    Ok(rx_path.into_iter().collect()) // Finishes once every sender clone is dropped
+}
 
-    // Removed: drop(tx_path); // No longer needed, and tx_path was moved
-
-    let paths_to_read: Vec<_> = rx_path.into_iter().collect(); // This will finish when all senders (clones) are dropped
-    log::info!(
-        "Found {} files matching pattern. Reading content...",
-        paths_to_read.len()
-    );
-
-    let results: Vec<Result<(String, String)>> = paths_to_read
-        .par_iter()
-        .map(|path| {
-            let content = fs::read_to_string(path)
-                .with_context(|| format!("Failed to read file {}", path.display()))?;
-            let relative_path = pathdiff::diff_paths(path, &project_root)
-                .unwrap_or_else(|| path.clone())
-                .to_string_lossy()
-                .to_string();
-            Ok((relative_path, content))
+// Tab-separated "path<TAB>bytes" lines followed by a total, sorted for stable piping
+fn print_match_counts(paths: &[(PathBuf, String)]) {
+    let mut entries: Vec<(&str, u64)> = paths
+        .iter()
+        .map(|(path, key)| {
+            let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+            (key.as_str(), size)
         })
         .collect();
+    entries.sort();
 
-    let mut files_map = HashMap::new();
-    let mut read_errors = Vec::new();
+    let total_bytes: u64 = entries.iter().map(|(_, size)| size).sum();
+    for (path, size) in &entries {
+        println!("{}\t{}", path, size);
+    }
+    println!("{} files, {} bytes", entries.len(), total_bytes);
+}
 
-    for result in results {
-        match result {
-            Ok((path_str, content)) => {
-                files_map.insert(path_str, content);
+// Reads matches in parallel windows and hands them to the writer over a bounded channel in
+// sorted path order, so output is deterministic and memory stays at roughly one window.
+fn stream_matched_files(
+    mut paths: Vec<(PathBuf, String)>,
+    format: &str,
+    quiet: bool,
+) -> Result<()> {
+    paths.sort_by(|a, b| a.1.cmp(&b.1));
+    let (tx, rx) = mpsc::sync_channel::<Result<(String, String)>>(STREAM_WINDOW);
+
+    thread::scope(|scope| {
+        scope.spawn(move || {
+            for window in paths.chunks(STREAM_WINDOW) {
+                let batch: Vec<Result<(String, String)>> = window
+                    .par_iter()
+                    .map(|(path, key)| {
+                        let content = fs::read_to_string(path)
+                            .with_context(|| format!("Failed to read file {}", path.display()))?;
+                        Ok((key.clone(), content))
+                    })
+                    .collect();
+                for item in batch {
+                    if tx.send(item).is_err() {
+                        return; // Writer stopped (e.g., stdout closed)
+                    }
+                }
             }
-            Err(e) => {
-                read_errors.push(format!("{:#}", e));
+        });
+
+        let stdout = io::stdout();
+        let mut out = io::BufWriter::new(stdout.lock());
+        let mut streamed = 0usize;
+        for item in rx {
+            match item {
+                Ok((path, content)) => {
+                    write_stream_entry(&mut out, format, &path, &content)?;
+                    streamed += 1;
+                }
+                Err(e) => {
+                    crate::record_warning();
+                    if !quiet {
+                        eprintln!("{} {:#}", "Warning:".yellow(), e);
+                    }
+                }
             }
         }
-    }
+        out.flush().context("Failed to flush stdout")?;
+        log::info!("Streamed {} matched files.", streamed);
+        Ok(())
+    })
+}
 
-    if !read_errors.is_empty() && !quiet {
-        eprintln!(
-            "{}",
-            "Warning: Errors encountered during file reading:".yellow()
-        );
-        for err_msg in read_errors {
-            eprintln!(" - {}", err_msg);
+fn write_stream_entry<W: Write>(
+    out: &mut W,
+    format: &str,
+    path: &str,
+    content: &str,
+) -> Result<()> {
+    match format {
+        "concat" => {
+            writeln!(out, "==> {} <==", path)?;
+            out.write_all(content.as_bytes())?;
+            if !content.ends_with('\n') {
+                writeln!(out)?;
+            }
+        }
+        _ => {
+            let line = serde_json::to_string(&QuickStreamEntry { path, content })?;
+            writeln!(out, "{}", line)?;
         }
-        eprintln!("---");
-    }
-
-    if files_map.is_empty() && !quiet {
-        println!("No files matched the pattern '{}'.", args.pattern);
-        return Ok(());
     }
-
-    let output_data = QuickOutput { files: files_map };
-
-    let default_format = "json";
-    print_data_or_text(
-        &output_data,
-        None,
-        &args.format_output,
-        default_format,
-        "QuickOutput",
-    )
+    Ok(())
 }
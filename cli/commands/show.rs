@@ -5,8 +5,8 @@ use anyhow::{Context, Result};
 use colored::*;
 use log; // Corrected: On its own line
 use serde::Serialize; // Needed for ShowOutputWrapper
-use std::collections::{BTreeMap, HashMap};
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use xcontext_core::{self as core, Config, ResolvedRules}; // Removed unused config import alias
 
 #[derive(Serialize)]
@@ -14,6 +14,16 @@ struct ShowOutputWrapper<T: Serialize> {
     value: T,
 }
 
+// Printed by `--summary` in place of the full rules/prompts/metas content
+#[derive(Serialize)]
+struct ShowSummary {
+    count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_rules: Option<usize>, // Rule sets only
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    by_origin: BTreeMap<String, usize>,
+}
+
 pub fn handle_show_command(args: ShowArgs, quiet: bool, verbose: u8) -> Result<()> {
     let project_root = Config::determine_project_root(args.project_config.project_root.as_ref())
         .context("Failed to determine project root")?;
@@ -33,8 +43,8 @@ pub fn handle_show_command(args: ShowArgs, quiet: bool, verbose: u8) -> Result<(
         crate::cli_args::ShowItem::Meta { key } => {
             handle_show_meta_singular(&config, key.as_deref(), &args.format_output, quiet, verbose)
         }
-        crate::cli_args::ShowItem::Metas {} => {
-            handle_show_meta_plural(&config, &args.format_output, quiet, verbose)
+        crate::cli_args::ShowItem::Metas { summary } => {
+            handle_show_meta_plural(&config, *summary, &args.format_output, quiet, verbose)
         }
         crate::cli_args::ShowItem::Prompt { name } => handle_show_prompt_singular(
             &config,
@@ -43,9 +53,19 @@ pub fn handle_show_command(args: ShowArgs, quiet: bool, verbose: u8) -> Result<(
             quiet,
             verbose,
         ),
-        crate::cli_args::ShowItem::Prompts {} => {
-            handle_show_prompt_plural(&config, &args.format_output, quiet, verbose)
-        }
+        crate::cli_args::ShowItem::Prompts {
+            static_only,
+            preview,
+            summary,
+        } => handle_show_prompt_plural(
+            &config,
+            *static_only,
+            *preview,
+            *summary,
+            &args.format_output,
+            quiet,
+            verbose,
+        ),
         crate::cli_args::ShowItem::Rule { name } => handle_show_rule_singular(
             &config,
             name.as_deref(),
@@ -54,9 +74,29 @@ pub fn handle_show_command(args: ShowArgs, quiet: bool, verbose: u8) -> Result<(
             quiet,
             verbose,
         ),
-        crate::cli_args::ShowItem::Rules {} => handle_show_rule_plural(
+        crate::cli_args::ShowItem::Rules {
+            available: true, ..
+        } => {
+            let stems = core::list_static_rules();
+            let plain_text = stems.join("\n");
+            print_data_or_text(
+                &stems,
+                Some(plain_text),
+                &args.format_output,
+                "text",
+                "Rules",
+            )
+        }
+        crate::cli_args::ShowItem::Ignores { ignore_files } => {
+            handle_show_ignores(&config, ignore_files, &args.format_output)
+        }
+        crate::cli_args::ShowItem::Rules {
+            available: false,
+            summary,
+        } => handle_show_rule_plural(
             &config,
             &project_root, // Pass project root for rule resolution
+            *summary,
             &args.format_output,
             quiet,
             verbose,
@@ -64,14 +104,14 @@ pub fn handle_show_command(args: ShowArgs, quiet: bool, verbose: u8) -> Result<(
     }
 }
 
-fn list_available_keys(map: &HashMap<String, String>, item_type: &str, _quiet: bool) {
+fn list_available_keys<'a>(keys: impl Iterator<Item = &'a String>, item_type: &str, _quiet: bool) {
     // Always print key listing to stderr
     eprintln!("\nAvailable {} keys:", item_type.bold());
-    if map.is_empty() {
+    let mut sorted_keys: Vec<_> = keys.collect();
+    if sorted_keys.is_empty() {
         eprintln!("  {}", "(None available)".dimmed());
         return;
     }
-    let mut sorted_keys: Vec<_> = map.keys().collect();
     sorted_keys.sort();
     for key in sorted_keys {
         eprintln!("  - {}", key.blue());
@@ -96,8 +136,9 @@ fn list_available_rule_keys(resolved_rules: &ResolvedRules, _quiet: bool) {
             "default" | "default+include" => origin.cyan(),
             "dynamic" => origin.magenta(),
             "include" => origin.green(),
-            "import" => origin.yellow(),
+            "import" | "import-ref" => origin.yellow(),
             "custom" => origin.blue(),
+            _ if origin.starts_with("inherited:") => origin.yellow(),
             _ => origin.dimmed(),
         };
         eprintln!("  - {} {}", key.blue(), format!("({})", origin_colored));
@@ -137,7 +178,7 @@ fn handle_show_meta_singular(
                 "Error:".red(),
                 k.blue()
             );
-            list_available_keys(meta_data, "metadata", quiet);
+            list_available_keys(meta_data.keys(), "metadata", quiet);
             anyhow::bail!("Metadata key not found") // Use anyhow::bail!
         }
     } else {
@@ -147,13 +188,14 @@ fn handle_show_meta_singular(
                 "Please specify a metadata key to show, or use 'show metas' to list all.".yellow()
             );
         }
-        list_available_keys(meta_data, "metadata", quiet);
+        list_available_keys(meta_data.keys(), "metadata", quiet);
         Ok(())
     }
 }
 
 fn handle_show_meta_plural(
     config: &Config,
+    summary: bool,
     format_opts: &FormatOutputOpts,
     quiet: bool,
     _verbose: u8,
@@ -175,6 +217,15 @@ fn handle_show_meta_plural(
         return Ok(());
     }
 
+    if summary {
+        let counts = ShowSummary {
+            count: meta_data.len(),
+            total_rules: None,
+            by_origin: BTreeMap::new(),
+        };
+        return print_show_summary(&counts, "Custom Metadata", "Keys", format_opts);
+    }
+
     let sorted_meta: BTreeMap<_, _> = meta_data.iter().collect();
 
     let pretty_text = if format_opts.format.is_none() {
@@ -233,7 +284,7 @@ fn handle_show_prompt_singular(
             )
         } else {
             eprintln!("{} Prompt name \"{}\" not found.", "Error:".red(), n.blue());
-            list_available_keys(&merged_prompts, "prompt", quiet);
+            list_available_keys(merged_prompts.keys(), "prompt", quiet);
             anyhow::bail!("Prompt name not found")
         }
     } else {
@@ -243,19 +294,30 @@ fn handle_show_prompt_singular(
                 "Please specify a prompt name to show, or use 'show prompts' to show all.".yellow()
             );
         }
-        list_available_keys(&merged_prompts, "prompt", quiet);
+        list_available_keys(merged_prompts.keys(), "prompt", quiet);
         Ok(())
     }
 }
 
 fn handle_show_prompt_plural(
     config: &Config,
+    static_only: bool,
+    preview: Option<usize>,
+    summary: bool,
     format_opts: &FormatOutputOpts,
     quiet: bool,
     _verbose: u8,
 ) -> Result<()> {
-    let merged_prompts = core::config::resolve_prompts(&config.prompts, Path::new(".")) // Pass dummy path
+    let mut merged_prompts = core::config::resolve_prompts(&config.prompts, Path::new(".")) // Pass dummy path
         .context("Failed to resolve prompts")?;
+    if static_only {
+        merged_prompts.retain(|key, _| key.starts_with("static:"));
+    }
+    if let Some(max_chars) = preview {
+        for text in merged_prompts.values_mut() {
+            *text = preview_text(text, max_chars);
+        }
+    }
 
     if merged_prompts.is_empty() {
         if !quiet {
@@ -264,6 +326,15 @@ fn handle_show_prompt_plural(
         return Ok(());
     }
 
+    if summary {
+        let counts = ShowSummary {
+            count: merged_prompts.len(),
+            total_rules: None,
+            by_origin: count_by(merged_prompts.keys().map(|key| prompt_origin(key))),
+        };
+        return print_show_summary(&counts, "Prompts", "Prompts", format_opts);
+    }
+
     let sorted_prompts: BTreeMap<_, _> = merged_prompts.iter().collect();
 
     let pretty_text = if format_opts.format.is_none() {
@@ -286,6 +357,63 @@ fn handle_show_prompt_plural(
     print_data_or_text(&sorted_prompts, pretty_text, format_opts, "text", "Prompts")
 }
 
+// Origin of a prompt key is its prefix: "static", "custom" or "imported"
+fn prompt_origin(key: &str) -> &str {
+    key.split_once(':').map_or("?", |(origin, _)| origin)
+}
+
+fn count_by<'a>(origins: impl Iterator<Item = &'a str>) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for origin in origins {
+        *counts.entry(origin.to_string()).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn print_show_summary(
+    summary: &ShowSummary,
+    title: &str,
+    count_label: &str,
+    format_opts: &FormatOutputOpts,
+) -> Result<()> {
+    let pretty_text = if format_opts.format.is_none() {
+        let mut output = format!(
+            "{}\n",
+            format!("\n--- {} Summary ---", title)
+                .green()
+                .bold()
+                .underline()
+        );
+        output.push_str(&format!(
+            "  {:<12} : {}\n",
+            count_label.blue(),
+            summary.count
+        ));
+        if let Some(total) = summary.total_rules {
+            output.push_str(&format!("  {:<12} : {}\n", "Rules".blue(), total));
+        }
+        if !summary.by_origin.is_empty() {
+            output.push_str(&format!("  {}\n", "By origin:".blue()));
+            for (origin, count) in &summary.by_origin {
+                output.push_str(&format!("    {:<20} : {}\n", origin, count));
+            }
+        }
+        Some(output)
+    } else {
+        None
+    };
+    print_data_or_text(summary, pretty_text, format_opts, "text", "Summary")
+}
+
+// Collapses whitespace so previews stay on one line, then cuts at `max_chars`
+fn preview_text(text: &str, max_chars: usize) -> String {
+    let flattened = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match flattened.char_indices().nth(max_chars) {
+        Some((cut, _)) => format!("{}...", &flattened[..cut]),
+        None => flattened,
+    }
+}
+
 fn handle_show_rule_singular(
     config: &Config,
     name_with_optional_prefix: Option<&str>,
@@ -306,9 +434,15 @@ fn handle_show_rule_singular(
 
     let project_characteristics = core::detect_project_characteristics(project_root)
         .context("Failed to detect project characteristics for rule resolution")?;
-    let resolved =
-        core::config::resolve_rules(&config.rules, project_root, &project_characteristics)
-            .context("Failed to resolve rules")?;
+    let scope_paths = gather_paths_for_rule_scopes(project_root, config, quiet)?;
+    let gathered_paths: Vec<&Path> = scope_paths.iter().map(PathBuf::as_path).collect();
+    let resolved = core::config::resolve_rules(
+        &config.rules,
+        project_root,
+        &project_characteristics,
+        &gathered_paths,
+    )
+    .context("Failed to resolve rules")?;
 
     if let Some(name) = name_with_optional_prefix {
         let stem_name = name.split(':').last().unwrap_or(name);
@@ -358,6 +492,7 @@ fn handle_show_rule_singular(
 fn handle_show_rule_plural(
     config: &Config,
     project_root: &Path, // Need project root to resolve rules
+    summary: bool,
     format_opts: &FormatOutputOpts,
     quiet: bool,
     _verbose: u8,
@@ -374,9 +509,15 @@ fn handle_show_rule_plural(
 
     let project_characteristics = core::detect_project_characteristics(project_root)
         .context("Failed to detect project characteristics for rule resolution")?;
-    let resolved =
-        core::config::resolve_rules(&config.rules, project_root, &project_characteristics)
-            .context("Failed to resolve rules")?;
+    let scope_paths = gather_paths_for_rule_scopes(project_root, config, quiet)?;
+    let gathered_paths: Vec<&Path> = scope_paths.iter().map(PathBuf::as_path).collect();
+    let resolved = core::config::resolve_rules(
+        &config.rules,
+        project_root,
+        &project_characteristics,
+        &gathered_paths,
+    )
+    .context("Failed to resolve rules")?;
 
     if resolved.rulesets.is_empty() {
         if !quiet {
@@ -387,6 +528,20 @@ fn handle_show_rule_plural(
         return Ok(());
     }
 
+    if summary {
+        let counts = ShowSummary {
+            count: resolved.rulesets.len(),
+            total_rules: Some(resolved.rulesets.values().map(Vec::len).sum()),
+            by_origin: count_by(
+                resolved
+                    .rulesets
+                    .keys()
+                    .map(|key| resolved.origins.get(key).map_or("?", String::as_str)),
+            ),
+        };
+        return print_show_summary(&counts, "Rule Sets", "Rule sets", format_opts);
+    }
+
     let rules_to_output = &resolved.rulesets;
     let sorted_rules: BTreeMap<_, _> = rules_to_output.iter().collect();
 
@@ -402,8 +557,9 @@ fn handle_show_rule_plural(
                 "default" | "default+include" => origin.cyan(),
                 "dynamic" => origin.magenta(),
                 "include" => origin.green(),
-                "import" => origin.yellow(),
+                "import" | "import-ref" => origin.yellow(),
                 "custom" => origin.blue(),
+                _ if origin.starts_with("inherited:") => origin.yellow(),
                 _ => origin.dimmed(),
             };
             output.push_str(&format!(
@@ -426,3 +582,141 @@ fn handle_show_rule_plural(
 
     print_data_or_text(&sorted_rules, pretty_text, format_opts, "text", "RuleSets")
 }
+
+// `[rules.scoped]` depends on which files would be gathered; skip the walk when none are configured
+fn gather_paths_for_rule_scopes(
+    project_root: &Path,
+    config: &Config,
+    quiet: bool,
+) -> Result<Vec<PathBuf>> {
+    if config.rules.scoped.is_empty() {
+        return Ok(Vec::new());
+    }
+    let gathered = core::gather_files_and_tree(project_root, config, quiet)
+        .context("Failed to gather files for scoped rules")?;
+    Ok(gathered
+        .source_files
+        .into_iter()
+        .chain(gathered.docs_files)
+        .map(|f| f.path)
+        .collect())
+}
+
+#[derive(Serialize)]
+struct IgnoresReport<'a> {
+    builtin_enabled: bool,
+    gitignore: bool,
+    dockerignore: bool,
+    exclude_untracked: bool,
+    ignore_files: &'a [PathBuf],
+    builtin_common: &'a [String],
+    sections: Vec<SectionIgnores<'a>>,
+}
+
+#[derive(Serialize)]
+struct SectionIgnores<'a> {
+    section: &'static str,
+    enabled: bool,
+    gitignore: bool,
+    builtin: &'a [String],
+    exclude: &'a [String], // Section excludes, or the common ones when the section sets none
+}
+
+fn handle_show_ignores(
+    config: &Config,
+    ignore_files: &[PathBuf],
+    format_opts: &FormatOutputOpts,
+) -> Result<()> {
+    let builtin = core::get_builtin_ignore_patterns();
+    let section = |section, enabled, use_gitignore, builtin, exclude| SectionIgnores {
+        section,
+        enabled,
+        gitignore: config.get_effective_gitignore(use_gitignore),
+        builtin,
+        exclude: config.get_effective_exclude(exclude),
+    };
+    let report = IgnoresReport {
+        builtin_enabled: config.get_effective_builtin_ignore(),
+        gitignore: config.general.use_gitignore,
+        dockerignore: config.general.respect_dockerignore,
+        exclude_untracked: config.general.exclude_untracked,
+        ignore_files,
+        builtin_common: &builtin.common,
+        sections: vec![
+            section(
+                "tree",
+                config.tree.enabled,
+                &config.tree.use_gitignore,
+                &builtin.tree,
+                &config.tree.exclude,
+            ),
+            section(
+                "source",
+                config.source.enabled,
+                &config.source.use_gitignore,
+                &builtin.source,
+                &config.source.exclude,
+            ),
+            section(
+                "docs",
+                config.is_docs_section_active(),
+                &config.docs.use_gitignore,
+                &builtin.docs,
+                &config.docs.exclude,
+            ),
+        ],
+    };
+
+    let pretty_text = if format_opts.format.is_none() {
+        let yes_no = |flag: bool| if flag { "yes".green() } else { "no".dimmed() };
+        let mut output = String::new();
+        output.push_str(&format!(
+            "{}\n",
+            "\n--- Effective Ignore Set ---".green().bold().underline()
+        ));
+        output.push_str(&format!(
+            "\nBuilt-in ignores: {}\n",
+            yes_no(report.builtin_enabled)
+        ));
+        output.push_str(&format!(".gitignore: {}\n", yes_no(report.gitignore)));
+        output.push_str(&format!(".dockerignore: {}\n", yes_no(report.dockerignore)));
+        output.push_str(&format!(
+            "Untracked files excluded: {}\n",
+            yes_no(report.exclude_untracked)
+        ));
+        for path in report.ignore_files {
+            output.push_str(&format!("Extra ignore file: {}\n", path.display()));
+        }
+
+        let push_patterns = |output: &mut String, label: &str, patterns: &[String]| {
+            output.push_str(&format!("  {}:\n", label));
+            if patterns.is_empty() {
+                output.push_str(&format!("    {}\n", "(none)".dimmed()));
+            }
+            for pattern in patterns {
+                output.push_str(&format!("    {}\n", pattern));
+            }
+        };
+        output.push_str(&format!("\n▶ {}:\n", "common".blue().bold()));
+        push_patterns(&mut output, "built-in", report.builtin_common);
+        for section in &report.sections {
+            let status = if section.enabled {
+                format!("(gitignore: {})", yes_no(section.gitignore))
+            } else {
+                "(disabled)".dimmed().to_string()
+            };
+            output.push_str(&format!(
+                "\n▶ {} {}:\n",
+                section.section.blue().bold(),
+                status
+            ));
+            push_patterns(&mut output, "built-in", section.builtin);
+            push_patterns(&mut output, "configured exclude", section.exclude);
+        }
+        Some(output)
+    } else {
+        None
+    };
+
+    print_data_or_text(&report, pretty_text, format_opts, "text", "Ignores")
+}
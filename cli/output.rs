@@ -2,41 +2,48 @@ use anyhow::{Context, Result};
 use colored::*;
 // Corrected: Separate use statements onto different lines
 use comfy_table::{Cell, Color, ContentArrangement, Table, presets::UTF8_FULL};
+use hmac::{Hmac, Mac};
 use serde::Serialize;
+use sha2::{Digest, Sha256};
 use std::fs::{self, File};
 use std::io::{self, Write};
-use std::path::Path;
-use xcontext_core::{ChunkFile, ProjectContext, output_formats}; // Use core types
+use std::path::{Path, PathBuf};
+use xcontext_core::config::{OutputConfig, PrettyMode};
+use xcontext_core::{ChunkFile, OUTPUT_SECTIONS, ProjectContext, output_formats}; // Use core types
 
 use crate::cli_args::FormatOutputOpts; // Use CLI format options
 
 // --- Public Output Functions ---
 
+// The serialized main context, kept so the post-generation summary can measure it
+pub struct WrittenOutput {
+    pub content: String,
+}
+
 pub fn print_context_or_save(
     context: &ProjectContext,
     config: &xcontext_core::Config, // Use core config
     output_path: Option<&Path>,
     format_opts: &FormatOutputOpts,
     quiet: bool,
-) -> Result<()> {
-    let final_format = format_opts
-        .format
-        .as_deref()
-        .unwrap_or(&config.output.format);
-    let pretty_json = !config.output.json_minify; // Use config value after overrides
-    let pretty_xml = config.output.xml_pretty_print; // Use config value after overrides
+) -> Result<WrittenOutput> {
+    let final_format = explicit_format(format_opts).unwrap_or(&config.output.format);
+    let pretty = config.output.pretty_for(final_format); // Use config value after overrides
 
-    let content = serialize_output(
+    let content = serialize_project_context(
         context,
+        config,
         final_format,
-        pretty_json,
-        pretty_xml,
-        "ProjectContext",
+        pretty,
+        format_opts
+            .format_fallback
+            .as_deref()
+            .map(|f| (f, config.output.pretty_for(f))),
     )?;
 
     match output_path {
         Some(path) => {
-            write_to_file(path, &content)?;
+            write_to_file(path, &content, config.save.atomic_write)?;
             let is_chunked = context.source.as_ref().is_some_and(|s| s.chunks.is_some());
             if !is_chunked && !quiet {
                 println!(
@@ -47,23 +54,178 @@ pub fn print_context_or_save(
             }
         }
         None => {
-            write_to_stdout(&content)?;
+            write_to_stdout(&content, config.output.trailing_newline)?;
         }
     }
+    Ok(WrittenOutput { content })
+}
+
+// One-line summary after generation; goes to stderr so piped stdout stays clean
+pub fn print_generation_summary(context: &ProjectContext, written: &WrittenOutput) {
+    let source = context.source.as_ref();
+    let source_files = source.and_then(|s| s.files.as_ref()).map_or(0, Vec::len);
+    let chunks = source.and_then(|s| s.chunks.as_ref()).map_or(0, Vec::len);
+    let docs_files = context.docs.as_ref().map_or(0, Vec::len);
+    // Tokens are only counted when the summary is shown; a tokenizer failure must not fail a save
+    let content = &written.content;
+    let estimated_tokens = xcontext_core::tokens::estimate_tokens(content)
+        .unwrap_or_else(|_| xcontext_core::tokens::approximate_tokens(content));
+    let mut summary = format!(
+        "{} bytes, ~{} tokens, {} source files, {} docs files",
+        content.len(),
+        estimated_tokens,
+        source_files,
+        docs_files
+    );
+    if chunks > 0 {
+        summary.push_str(&format!(", {} chunks", chunks));
+    }
+    eprintln!("{} {}", "Summary:".dimmed(), summary.dimmed());
+}
+
+pub fn save_context_in_format(
+    context: &ProjectContext,
+    config: &xcontext_core::Config,
+    output_path: &Path,
+    format: &str,
+    quiet: bool,
+) -> Result<()> {
+    let content = serialize_project_context(
+        context,
+        config,
+        format,
+        config.output.pretty_for(format),
+        None,
+    )?;
+    write_to_file(output_path, &content, config.save.atomic_write)?;
+    if !quiet {
+        println!(
+            "{} Context saved to: {}",
+            "✅".green(),
+            output_path.display().to_string().blue()
+        );
+    }
     Ok(())
 }
 
+#[derive(Serialize)]
+struct ManifestEntry {
+    path: String,
+    bytes: u64,
+    sha256: String,
+}
+
+#[derive(Serialize)]
+struct Manifest {
+    files: Vec<ManifestEntry>,
+}
+
+// Hashes are taken from the files as written, so the manifest matches what is on disk
+pub fn write_checksum_manifest(
+    save_dir: &Path,
+    files: &[PathBuf],
+    atomic: bool,
+    quiet: bool,
+) -> Result<()> {
+    let mut entries = Vec::with_capacity(files.len());
+    for path in files {
+        let bytes = fs::read(path)
+            .with_context(|| format!("Failed to read {} for manifest", path.display()))?;
+        let relative = path.strip_prefix(save_dir).unwrap_or(path);
+        entries.push(ManifestEntry {
+            path: relative.to_string_lossy().replace('\\', "/"),
+            bytes: bytes.len() as u64,
+            sha256: format!("{:x}", Sha256::digest(&bytes)),
+        });
+    }
+    let manifest_path = save_dir.join("manifest.json");
+    let content = output_formats::serialize_context_to_json(&Manifest { files: entries }, true)?;
+    write_to_file(&manifest_path, &content, atomic)?;
+    if !quiet {
+        println!(
+            "{} Manifest saved to: {}",
+            "🧾".blue(),
+            manifest_path.display().to_string().dimmed()
+        );
+    }
+    Ok(())
+}
+
+// Env var holding the key for the optional HMAC in provenance sidecars
+pub const SIGNING_KEY_ENV: &str = "XCONTEXT_SIGNING_KEY";
+
+#[derive(Serialize)]
+struct Provenance {
+    file: String,
+    sha256: String,
+    tool: &'static str,
+    tool_version: &'static str,
+    generated_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hmac_sha256: Option<String>,
+}
+
+// Writes '<base>.xcontext.prov' next to `context_path`, recording the context's SHA-256 and, when
+// XCONTEXT_SIGNING_KEY is set, an HMAC-SHA256 over the same bytes
+pub fn write_provenance(context_path: &Path, atomic: bool, quiet: bool) -> Result<PathBuf> {
+    let bytes = fs::read(context_path)
+        .with_context(|| format!("Failed to read {} for signing", context_path.display()))?;
+    let hmac_sha256 = std::env::var(SIGNING_KEY_ENV)
+        .ok()
+        .filter(|key| !key.is_empty())
+        .map(|key| hmac_sha256_hex(key.as_bytes(), &bytes));
+    let provenance = Provenance {
+        file: context_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        sha256: format!("{:x}", Sha256::digest(&bytes)),
+        tool: env!("CARGO_BIN_NAME"),
+        tool_version: env!("CARGO_PKG_VERSION"),
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        hmac_sha256,
+    };
+    let stem = context_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let prov_path = context_path.with_file_name(format!("{}.xcontext.prov", stem));
+    let content = output_formats::serialize_context_to_json(&provenance, true)?;
+    write_to_file(&prov_path, &content, atomic)?;
+    if !quiet {
+        println!(
+            "{} Provenance saved to: {}{}",
+            "🔏".blue(),
+            prov_path.display().to_string().dimmed(),
+            if provenance.hmac_sha256.is_some() {
+                " (HMAC signed)"
+            } else {
+                ""
+            }
+        );
+    }
+    Ok(prov_path)
+}
+
+fn hmac_sha256_hex(key: &[u8], message: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(message);
+    format!("{:x}", mac.finalize().into_bytes())
+}
+
 pub fn save_chunk_file(
     chunk_data: &ChunkFile,
     path: &Path,
     format_opts: &FormatOutputOpts, // Use CLI format opts for chunk format
+    atomic: bool,
     quiet: bool,
 ) -> Result<()> {
     // Chunks are always JSON for now, respect pretty/minify from CLI args
-    let pretty = !format_opts.disable_json_minify || format_opts.enable_json_minify;
+    let pretty = pretty_override(format_opts)
+        .unwrap_or(!format_opts.disable_json_minify || format_opts.enable_json_minify);
     let content = output_formats::serialize_context_to_json(chunk_data, pretty)?;
 
-    write_to_file(path, &content)?;
+    write_to_file(path, &content, atomic)?;
     if !quiet {
         println!(
             "{} Chunk saved to: {}",
@@ -74,6 +236,68 @@ pub fn save_chunk_file(
     Ok(())
 }
 
+// YAML chunks go into one multi-document stream instead of a file per chunk
+pub fn save_chunk_stream(
+    chunks: &[ChunkFile],
+    path: &Path,
+    config: &xcontext_core::Config,
+    quiet: bool,
+) -> Result<()> {
+    let pretty = config.output.pretty_for("yaml");
+    let content = output_formats::serialize_yaml_documents(chunks, pretty)?;
+
+    write_to_file(path, &content, config.save.atomic_write)?;
+    if !quiet {
+        println!(
+            "{} {} chunk(s) saved to: {}",
+            "📦".blue(),
+            chunks.len(),
+            path.display().to_string().dimmed()
+        );
+    }
+    Ok(())
+}
+
+// 'auto' is resolved into config.output.format during config loading, so it defers to the config here
+pub fn explicit_format(format_opts: &FormatOutputOpts) -> Option<&str> {
+    format_opts.format.as_deref().filter(|f| *f != "auto")
+}
+
+// Maps a save path's extension to an output format, e.g. 'out.yml' -> 'yaml'
+pub fn format_from_path(path: &Path) -> Option<&'static str> {
+    match path.extension()?.to_str()?.to_lowercase().as_str() {
+        "json" => Some("json"),
+        "yaml" | "yml" => Some("yaml"),
+        "xml" => Some("xml"),
+        "txt" => Some("doc-tags"),
+        "md" | "markdown" => Some("markdown"),
+        _ => None,
+    }
+}
+
+// Plain-text outputs (e.g. the `--docs-only` bundle) that bypass the structured serializers
+pub fn print_or_save_text(
+    content: &str,
+    output_path: Option<&Path>,
+    atomic: bool,
+    quiet: bool,
+) -> Result<()> {
+    match output_path {
+        Some(path) => {
+            write_to_file(path, content, atomic)?;
+            if !quiet {
+                println!(
+                    "{} Output saved to: {}",
+                    "✅".green(),
+                    path.display().to_string().blue()
+                );
+            }
+            Ok(())
+        }
+        None => write_to_stdout(content, true),
+    }
+}
+
 // Helper for commands that might output structured data or plain text
 pub fn print_data_or_text<T: Serialize>(
     data: &T,
@@ -82,59 +306,218 @@ pub fn print_data_or_text<T: Serialize>(
     default_format: &str, // e.g., "json" or "text"
     root_name: &str,      // For XML root element
 ) -> Result<()> {
-    let format = format_opts
-        .format
-        .as_deref()
+    let format = explicit_format(format_opts)
         .unwrap_or(default_format)
         .to_lowercase();
 
     if format == "text" {
         match plain_text {
-            Some(text) => write_to_stdout(&text),
+            Some(text) => write_to_stdout(&text, true),
             None => {
                 // Fallback to JSON pretty print if text is not available but format is text
                 let pretty = true;
                 let content = output_formats::serialize_context_to_json(data, pretty)?;
-                write_to_stdout(&content)
+                write_to_stdout(&content, true)
             }
         }
     } else {
-        let pretty_json = !format_opts.disable_json_minify;
-        let pretty_xml = format_opts.enable_xml_pretty;
-        let content = serialize_output(data, &format, pretty_json, pretty_xml, root_name)?;
-        write_to_stdout(&content)
+        let pretty = data_pretty(format_opts, &format);
+        let content = serialize_output(
+            data,
+            &format,
+            pretty,
+            (root_name, None),
+            data_fallback(format_opts),
+        )?;
+        write_to_stdout(&content, true)
+    }
+}
+
+// File counterpart of print_data_or_text for structured data
+pub fn save_data_to_file<T: Serialize>(
+    data: &T,
+    path: &Path,
+    format_opts: &FormatOutputOpts,
+    root_name: &str,
+    quiet: bool,
+) -> Result<()> {
+    let format = explicit_format(format_opts)
+        .unwrap_or("json")
+        .to_lowercase();
+    let pretty = data_pretty(format_opts, &format);
+    let content = serialize_output(
+        data,
+        &format,
+        pretty,
+        (root_name, None),
+        data_fallback(format_opts),
+    )?;
+    write_to_file(path, &content, false)?;
+    if !quiet {
+        println!(
+            "{} Output saved to: {}",
+            "✅".green(),
+            path.display().to_string().blue()
+        );
     }
+    Ok(())
 }
 
 // --- Internal Helpers ---
 
+// 'doc-tags' and 'markdown' only apply to ProjectContext; everything else goes through the generic
+// serializers.
+// A non-empty `output.sections` replaces the struct's field order and selection.
+fn serialize_project_context(
+    context: &ProjectContext,
+    config: &xcontext_core::Config,
+    format: &str,
+    pretty: bool,
+    fallback: Option<(&str, bool)>,
+) -> Result<String> {
+    let output_config = &config.output;
+    match format.to_lowercase().as_str() {
+        "doc-tags" => {
+            warn_sections_ignored(output_config, "doc-tags");
+            return Ok(output_formats::serialize_context_to_doc_tags(context));
+        }
+        "markdown" | "md" => {
+            warn_sections_ignored(output_config, "markdown");
+            return Ok(output_formats::serialize_context_to_markdown(context));
+        }
+        _ => {}
+    }
+    let xml_root = (
+        output_config
+            .xml_root
+            .as_deref()
+            .unwrap_or("ProjectContext"),
+        output_config.xml_namespace.as_deref(),
+    );
+    let sections = &output_config.sections;
+    if sections.is_empty() {
+        return serialize_output(context, format, pretty, xml_root, fallback);
+    }
+    for unknown in sections
+        .iter()
+        .filter(|name| !OUTPUT_SECTIONS.contains(&name.as_str()))
+    {
+        log::warn!(
+            "Unknown section '{}' in output.sections; expected one of: {}",
+            unknown,
+            OUTPUT_SECTIONS.join(", ")
+        );
+    }
+    let ordered = context
+        .ordered_sections(sections, config)
+        .context("Failed to order output sections")?;
+    serialize_output(&ordered, format, pretty, xml_root, fallback)
+}
+
+// The text formats have a fixed layout, so a configured `output.sections` cannot apply to them
+fn warn_sections_ignored(output_config: &OutputConfig, format: &str) {
+    if !output_config.sections.is_empty() {
+        log::warn!(
+            "output.sections has no effect on '{}' output; its sections keep their fixed layout",
+            format
+        );
+    }
+}
+
+// `--pretty always|never` wins over the deprecated per-format flags; `auto` defers to them
+fn pretty_override(format_opts: &FormatOutputOpts) -> Option<bool> {
+    match format_opts
+        .pretty
+        .as_deref()
+        .and_then(PrettyMode::from_name)?
+    {
+        PrettyMode::Always => Some(true),
+        PrettyMode::Never => Some(false),
+        PrettyMode::Auto => None,
+    }
+}
+
+fn data_pretty(format_opts: &FormatOutputOpts, format: &str) -> bool {
+    pretty_override(format_opts).unwrap_or(match format {
+        "json" => !format_opts.disable_json_minify,
+        "xml" => format_opts.enable_xml_pretty,
+        _ => true,
+    })
+}
+
+fn data_fallback(format_opts: &FormatOutputOpts) -> Option<(&str, bool)> {
+    let fallback = format_opts.format_fallback.as_deref()?;
+    Some((fallback, data_pretty(format_opts, fallback)))
+}
+
+// With `--format-fallback`, a serialization error in the primary format is downgraded to a warning
+// and the data is serialized again in the fallback format
 fn serialize_output<T: Serialize>(
     data: &T,
     format: &str,
-    pretty_json: bool,
-    pretty_xml: bool,
-    xml_root: &str,
+    pretty: bool,
+    xml_root: (&str, Option<&str>), // Root element name and its namespace
+    fallback: Option<(&str, bool)>, // Format and its pretty setting
+) -> Result<String> {
+    match (
+        serialize_in_format(data, format, pretty, xml_root),
+        fallback,
+    ) {
+        (Err(e), Some((fallback, fallback_pretty))) if !fallback.eq_ignore_ascii_case(format) => {
+            log::warn!(
+                "Serializing as {} failed ({:#}); falling back to {}.",
+                format,
+                e,
+                fallback
+            );
+            serialize_in_format(data, fallback, fallback_pretty, xml_root)
+        }
+        (result, _) => result,
+    }
+}
+
+fn serialize_in_format<T: Serialize>(
+    data: &T,
+    format: &str,
+    pretty: bool,
+    (xml_root, xml_namespace): (&str, Option<&str>),
 ) -> Result<String> {
     match format.to_lowercase().as_str() {
         "yaml" | "yml" => {
-            output_formats::serialize_context_to_yaml(data).map_err(anyhow::Error::from)
+            output_formats::serialize_context_to_yaml(data, pretty).map_err(anyhow::Error::from)
         }
-        "xml" => output_formats::serialize_context_to_xml(data, xml_root, pretty_xml)
+        "xml" => output_formats::serialize_context_to_xml(data, xml_root, xml_namespace, pretty)
             .map_err(anyhow::Error::from),
+        "doc-tags" | "markdown" | "md" => {
+            log::warn!(
+                "The '{}' format only applies to generate output; writing JSON instead.",
+                format
+            );
+            output_formats::serialize_context_to_json(data, pretty).map_err(anyhow::Error::from)
+        }
         "json" | _ => {
             // Default to JSON
-            output_formats::serialize_context_to_json(data, pretty_json)
-                .map_err(anyhow::Error::from)
+            output_formats::serialize_context_to_json(data, pretty).map_err(anyhow::Error::from)
         }
     }
 }
 
-fn write_to_file(path: &Path, content: &str) -> Result<()> {
+fn write_to_file(path: &Path, content: &str, atomic: bool) -> Result<()> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)
             .with_context(|| format!("Failed to create directory {}", parent.display()))?;
         // Added Context
     }
+    if atomic {
+        match write_atomically(path, content) {
+            Ok(()) => return Ok(()),
+            Err(e) => log::warn!(
+                "Atomic write to {} failed ({:#}); writing directly.",
+                path.display(),
+                e
+            ),
+        }
+    }
     let mut file =
         File::create(path).with_context(|| format!("Failed to create file {}", path.display()))?; // Added Context
     file.write_all(content.as_bytes())
@@ -142,20 +525,59 @@ fn write_to_file(path: &Path, content: &str) -> Result<()> {
     Ok(())
 }
 
-fn write_to_stdout(content: &str) -> Result<()> {
+// The temp file lives next to the target so the rename stays on one filesystem
+fn write_atomically(path: &Path, content: &str) -> Result<()> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let mut builder = tempfile::Builder::new();
+    // Keep the target's permissions instead of the 0600 temp-file default
+    if let Ok(metadata) = fs::metadata(path) {
+        builder.permissions(metadata.permissions());
+    } else {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            builder.permissions(fs::Permissions::from_mode(0o666)); // umask still applies
+        }
+    }
+    let mut temp = builder
+        .tempfile_in(dir)
+        .with_context(|| format!("Failed to create temp file in {}", dir.display()))?;
+    temp.write_all(content.as_bytes())
+        .with_context(|| format!("Failed to write temp file for {}", path.display()))?;
+    temp.as_file()
+        .sync_all()
+        .with_context(|| format!("Failed to sync temp file for {}", path.display()))?;
+    temp.persist(path)
+        .map_err(|e| e.error)
+        .with_context(|| format!("Failed to rename temp file into {}", path.display()))?;
+    Ok(())
+}
+
+fn write_to_stdout(content: &str, trailing_newline: bool) -> Result<()> {
     let stdout = io::stdout();
     let mut handle = stdout.lock();
-    handle
+    write_with_trailing_newline(&mut handle, content, trailing_newline)?;
+    handle.flush().context("Failed to flush stdout")?; // Added Context
+    Ok(())
+}
+
+// Appends a newline when the content lacks one, for better terminal behavior
+fn write_with_trailing_newline<W: Write>(
+    writer: &mut W,
+    content: &str,
+    trailing_newline: bool,
+) -> Result<()> {
+    writer
         .write_all(content.as_bytes())
         .context("Failed to write to stdout")?; // Added Context
-    // Add a newline if the content doesn't end with one, for better terminal behavior
-    // Corrected: Check for actual newline character '\n'
-    if !content.ends_with('\n') {
-        handle
-            .write_all(b"\\n") // Still write literal `\n` if adding one, common practice
+    if trailing_newline && !content.ends_with('\n') {
+        writer
+            .write_all(b"\n")
             .context("Failed to write newline to stdout")?; // Added Context
     }
-    handle.flush().context("Failed to flush stdout")?; // Added Context
     Ok(())
 }
 
@@ -180,11 +602,59 @@ pub fn print_metrics_pretty_table(
         "Total Size:".green(),
         metrics.total_bytes_readable.cyan()
     );
+    let approx_note = if metrics.estimated == "approximate" {
+        " (approximate)".dimmed().to_string()
+    } else {
+        String::new()
+    };
     println!(
-        "{:<20} {}",
+        "{:<20} {}{}",
         "Est. Tokens:".green(),
-        metrics.estimated_tokens.to_string().cyan()
+        metrics.estimated_tokens.to_string().cyan(),
+        approx_note
     );
+    if let Some(cost) = &metrics.estimated_cost {
+        let rate = match &cost.model {
+            Some(model) => format!("{} @ ${}/1K tokens", model, cost.price_per_1k_tokens),
+            None => format!("${}/1K tokens", cost.price_per_1k_tokens),
+        };
+        println!(
+            "{:<20} {} {}",
+            "Est. Input Cost:".green(),
+            format!("~${:.4}", cost.estimated_cost_usd).cyan(),
+            format!("({})", rate).dimmed()
+        );
+    }
+
+    if let Some(dirs) = &metrics.by_directory {
+        println!("\n{}", " By Directory ".green().bold().underline());
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic);
+        if !colored::control::SHOULD_COLORIZE.should_colorize() {
+            table.force_no_tty();
+        }
+        table.set_header(vec![
+            Cell::new("Directory").fg(Color::Green),
+            Cell::new("Files").fg(Color::Green),
+            Cell::new("Lines").fg(Color::Green),
+            Cell::new("Size").fg(Color::Green),
+            Cell::new("Tokens").fg(Color::Green),
+        ]);
+        for dir in dirs {
+            table.add_row(vec![
+                Cell::new(&dir.directory).fg(Color::Cyan),
+                Cell::new(dir.files).set_alignment(comfy_table::CellAlignment::Right),
+                Cell::new(dir.lines).set_alignment(comfy_table::CellAlignment::Right),
+                Cell::new(&dir.bytes_readable)
+                    .set_alignment(comfy_table::CellAlignment::Right)
+                    .fg(Color::DarkGrey),
+                Cell::new(dir.estimated_tokens).set_alignment(comfy_table::CellAlignment::Right),
+            ]);
+        }
+        println!("{table}");
+    }
 
     if metrics.files_details.is_empty() {
         println!("\n{}", "(No files included in metrics)".yellow());
@@ -194,6 +664,9 @@ pub fn print_metrics_pretty_table(
         table
             .load_preset(UTF8_FULL)
             .set_content_arrangement(ContentArrangement::Dynamic);
+        if !colored::control::SHOULD_COLORIZE.should_colorize() {
+            table.force_no_tty(); // Drops cell styling along with the rest of the colors
+        }
         table.set_header(vec![
             Cell::new("Path").fg(Color::Green),
             Cell::new("Lines").fg(Color::Green),
@@ -215,3 +688,48 @@ pub fn print_metrics_pretty_table(
     println!();
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn written(content: &str, trailing_newline: bool) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_with_trailing_newline(&mut buf, content, trailing_newline).unwrap();
+        buf
+    }
+
+    #[test]
+    fn trailing_newline_is_added_only_when_missing() {
+        assert_eq!(written("{}", true), b"{}\n");
+        assert_eq!(written("{}\n", true), b"{}\n");
+        assert_eq!(written("", true), b"\n");
+    }
+
+    #[test]
+    fn content_is_written_verbatim_without_trailing_newline() {
+        assert_eq!(written("{}", false), b"{}");
+        assert_eq!(written("{}\n", false), b"{}\n");
+        assert_eq!(written("", false), b"");
+    }
+
+    // RFC 4231 test cases 1, 2 and 6 (a key longer than the block size)
+    #[test]
+    fn hmac_sha256_matches_rfc_4231() {
+        assert_eq!(
+            hmac_sha256_hex(&[0x0b; 20], b"Hi There"),
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+        );
+        assert_eq!(
+            hmac_sha256_hex(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            hmac_sha256_hex(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            ),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use anyhow::{Context, Result};
+use colored::*;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::{LinesWithEndings, as_24_bit_terminal_escaped};
+use xcontext_core::{FileContextInfo, ProjectContext, output_formats};
+
+const PREVIEW_THEME: &str = "base16-ocean.dark";
+const RESET: &str = "\x1b[0m";
+
+// Renders the context for reading in a terminal: section headers, the tree with connectors and
+// each file highlighted by its extension. Only used when stdout is a TTY.
+pub fn print_pretty_preview(context: &ProjectContext) -> Result<()> {
+    let syntaxes = SyntaxSet::load_defaults_newlines();
+    let themes = ThemeSet::load_defaults();
+    let theme = themes
+        .themes
+        .get(PREVIEW_THEME)
+        .context("Default syntax theme is missing")?;
+    let mut out = BufWriter::new(io::stdout().lock());
+
+    if let Some(name) = &context.project_name {
+        write_header(&mut out, name)?;
+    }
+    if let Some(root) = &context.project_root {
+        writeln!(out, "{} {}", "Root:".dimmed(), root)?;
+    }
+    if let Some(tree) = context.tree.as_deref().filter(|t| !t.is_empty()) {
+        write_header(&mut out, "Tree")?;
+        let tree = output_formats::render_tree(tree, &|name| name.blue().bold().to_string());
+        write!(out, "{}", tree)?;
+    }
+    if let Some(docs) = context.docs.as_deref().filter(|d| !d.is_empty()) {
+        write_header(&mut out, "Docs")?;
+        for file in docs {
+            write_file(&mut out, file, &syntaxes, theme)?;
+        }
+    }
+    if let Some(source) = &context.source {
+        write_header(&mut out, "Source")?;
+        match (&source.files, &source.chunks) {
+            (Some(files), _) => {
+                for file in files {
+                    write_file(&mut out, file, &syntaxes, theme)?;
+                }
+            }
+            (None, Some(chunks)) => {
+                for chunk in chunks {
+                    writeln!(out, "{} {}", "chunk:".dimmed(), chunk)?;
+                }
+            }
+            (None, None) => {}
+        }
+    }
+    if !context.rules.is_empty() {
+        write_header(&mut out, "Rules")?;
+        for (name, lines) in &context.rules {
+            writeln!(out, "{}", name.bold())?;
+            for line in lines {
+                writeln!(out, "  {}", line)?;
+            }
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn write_header<W: Write>(out: &mut W, title: &str) -> Result<()> {
+    writeln!(out, "\n{}", format!("━━ {} ", title).bold().cyan())?;
+    Ok(())
+}
+
+fn write_file<W: Write>(
+    out: &mut W,
+    file: &FileContextInfo,
+    syntaxes: &SyntaxSet,
+    theme: &Theme,
+) -> Result<()> {
+    writeln!(out, "\n{}", format!("── {}", file.path).yellow().bold())?;
+    if let Some(note) = &file.note {
+        writeln!(out, "{}", note.dimmed())?;
+    }
+    let mut highlighter = HighlightLines::new(syntax_for_path(&file.path, syntaxes), theme);
+    for line in LinesWithEndings::from(&file.content) {
+        let ranges = highlighter
+            .highlight_line(line, syntaxes)
+            .with_context(|| format!("Failed to highlight {}", file.path))?;
+        write!(out, "{}", as_24_bit_terminal_escaped(&ranges, false))?;
+    }
+    if !file.content.ends_with('\n') {
+        writeln!(out)?;
+    }
+    write!(out, "{}", RESET)?;
+    Ok(())
+}
+
+// Extension first, then the bare file name for files like `Makefile` or `Dockerfile`
+fn syntax_for_path<'a>(path: &str, syntaxes: &'a SyntaxSet) -> &'a SyntaxReference {
+    let path = Path::new(path);
+    path.extension()
+        .and_then(|e| e.to_str())
+        .and_then(|ext| syntaxes.find_syntax_by_extension(ext))
+        .or_else(|| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|name| syntaxes.find_syntax_by_extension(name))
+        })
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text())
+}
@@ -6,8 +6,10 @@ use anyhow::{Context, Result};
 use colored::*;
 use log;
 // CORRECTED: Removed unused NotifyWatcher alias
-use notify::{ErrorKind, RecommendedWatcher};
-use notify_debouncer_mini::{Debouncer, new_debouncer};
+use notify::{ErrorKind, PollWatcher, Watcher};
+use notify_debouncer_mini::{
+    Config as DebouncerConfig, DebounceEventResult, Debouncer, new_debouncer, new_debouncer_opt,
+};
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, mpsc};
@@ -15,12 +17,13 @@ use xcontext_core::{self as core, Config}; // Use core types
 
 // Removed Watcher type alias
 
-fn watch_path(
-    watcher: &mut Debouncer<RecommendedWatcher>, // Use concrete type
+// Returns false only when the backend refused the watch; missing or already-watched paths are fine
+fn watch_path<W: Watcher>(
+    watcher: &mut Debouncer<W>,
     path: &Path,
     watched_paths: &mut HashSet<PathBuf>,
     quiet: bool,
-) -> Result<()> {
+) -> bool {
     // Attempt to canonicalize, fallback to original path if it fails (e.g., path doesn't exist yet)
     let path_to_watch = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
 
@@ -45,6 +48,7 @@ fn watch_path(
                     );
                 }
                 log::warn!("Failed to watch {}: {}", path.display(), e);
+                return false;
             }
         }
     } else if !path_to_watch.exists() {
@@ -52,13 +56,13 @@ fn watch_path(
     } else {
         log::trace!("Already watching: {}", path_to_watch.display());
     }
-    Ok(())
+    true
 }
 
-fn setup_watches(
+fn setup_watches<W: Watcher>(
     project_root: &Path,
     current_config: &Arc<Config>,
-    watcher: &mut Debouncer<RecommendedWatcher>, // Use concrete type
+    watcher: &mut Debouncer<W>,
     current_watched: &mut HashSet<PathBuf>,
     quiet: bool,
     verbose: u8,
@@ -91,18 +95,27 @@ fn setup_watches(
         }
     }
     current_watched.clear(); // Ensure it's empty before adding new ones
+    let mut failed_watches = 0;
 
     log::debug!("Setting up new watches based on current config...");
     match core::gather_files_and_tree(project_root, current_config, quiet) {
-        Ok((source_files, docs_files, _)) => {
+        Ok(core::GatherResult {
+            source_files,
+            docs_files,
+            ..
+        }) => {
             if current_config.source.enabled {
                 for file_info in source_files {
-                    let _ = watch_path(watcher, &file_info.path, current_watched, quiet);
+                    if !watch_path(watcher, &file_info.path, current_watched, quiet) {
+                        failed_watches += 1;
+                    }
                 }
             }
             if current_config.is_docs_section_active() {
                 for file_info in docs_files {
-                    let _ = watch_path(watcher, &file_info.path, current_watched, quiet);
+                    if !watch_path(watcher, &file_info.path, current_watched, quiet) {
+                        failed_watches += 1;
+                    }
                 }
             }
             // Watch imported rule files
@@ -114,7 +127,9 @@ fn setup_watches(
                         .join(rule_import_rel);
                 }
                 if path.exists() {
-                    let _ = watch_path(watcher, &path, current_watched, quiet);
+                    if !watch_path(watcher, &path, current_watched, quiet) {
+                        failed_watches += 1;
+                    }
                 } else {
                     log::warn!(
                         "Could not find imported rule file to watch: {}",
@@ -131,7 +146,9 @@ fn setup_watches(
                         .join(prompt_import_rel);
                 }
                 if path.exists() {
-                    let _ = watch_path(watcher, &path, current_watched, quiet);
+                    if !watch_path(watcher, &path, current_watched, quiet) {
+                        failed_watches += 1;
+                    }
                 } else {
                     log::warn!(
                         "Could not find imported prompt file to watch: {}",
@@ -156,8 +173,18 @@ fn setup_watches(
         None,  // Rely on default resolution logic here for watching
         false, // Don't disable checking for the config file
     )?;
-    if let Some(ref config_path) = config_path_to_watch {
-        let _ = watch_path(watcher, config_path, current_watched, quiet);
+    if let Some(ref config_path) = config_path_to_watch
+        && !watch_path(watcher, config_path, current_watched, quiet)
+    {
+        failed_watches += 1;
+    }
+
+    if failed_watches > 0 && !current_config.watch.force_poll && !quiet {
+        eprintln!(
+            "{} {} path(s) could not be watched natively. On network filesystems or container volumes, set watch.force_poll = true or pass --poll.",
+            "⚠️".yellow(),
+            failed_watches
+        );
     }
 
     if current_watched.is_empty() {
@@ -186,7 +213,7 @@ pub fn run_watch_mode(watch_args: WatchArgs, quiet: bool, verbose: u8) -> Result
         );
     }
 
-    let mut config = Arc::new(
+    let config = Arc::new(
         load_config_for_command(
             &project_root,
             &watch_args.project_config,
@@ -197,11 +224,29 @@ pub fn run_watch_mode(watch_args: WatchArgs, quiet: bool, verbose: u8) -> Result
         .context("Failed to load initial configuration for watch mode")?,
     );
 
+    if let Some(initial_delay) = config
+        .get_initial_delay()
+        .with_context(|| "Invalid watch initial delay")?
+    {
+        log::info!("Waiting {:?} before the initial generation.", initial_delay);
+        std::thread::sleep(initial_delay);
+    }
+
     let initial_output_target_args = OutputTargetArgs {
         save: &watch_args.save,
         chunks: &None, // Watch mode doesn't support chunking trigger
         stdout: watch_args.save.is_none(), // Default to stdout if not saving
         format_output: &watch_args.format_output,
+        also_format: &[],
+        no_banner: false,
+        max_tokens: None,
+        overview: false,
+        manifest_only: false,
+        docs_only: false,
+        with_metrics: false,
+        pretty_preview: false,
+        sign: false,
+        changed_paths: None, // The first run is always a full context
     };
 
     if let Err(e) = generate::trigger_generation(
@@ -222,12 +267,58 @@ pub fn run_watch_mode(watch_args: WatchArgs, quiet: bool, verbose: u8) -> Result
     let delay_duration = config
         .get_watch_delay()
         .with_context(|| "Invalid watch delay duration")?;
-    let mut debouncer = new_debouncer(delay_duration, tx)
-        .map_err(|e| anyhow::anyhow!("Failed to create debouncer: {}", e))?;
+    if config.watch.force_poll {
+        let poll_interval = config
+            .get_poll_interval()
+            .with_context(|| "Invalid watch poll interval")?;
+        log::info!("Using polling watcher (interval {:?}).", poll_interval);
+        let debouncer_config = DebouncerConfig::default()
+            .with_timeout(delay_duration)
+            .with_notify_config(notify::Config::default().with_poll_interval(poll_interval));
+        let debouncer = new_debouncer_opt::<_, PollWatcher>(debouncer_config, tx)
+            .map_err(|e| anyhow::anyhow!("Failed to create polling debouncer: {}", e))?;
+        watch_loop(
+            debouncer,
+            rx,
+            &project_root,
+            config,
+            &watch_args,
+            quiet,
+            verbose,
+        )
+    } else {
+        let debouncer = new_debouncer(delay_duration, tx).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to create native file watcher: {}. On network filesystems or container volumes, set watch.force_poll = true or pass --poll.",
+                e
+            )
+        })?;
+        watch_loop(
+            debouncer,
+            rx,
+            &project_root,
+            config,
+            &watch_args,
+            quiet,
+            verbose,
+        )
+    }
+}
+
+// Shared by the native and polling backends, which only differ in the debouncer's watcher type
+fn watch_loop<W: Watcher>(
+    mut debouncer: Debouncer<W>,
+    rx: mpsc::Receiver<DebounceEventResult>,
+    project_root: &Path,
+    mut config: Arc<Config>,
+    watch_args: &WatchArgs,
+    quiet: bool,
+    verbose: u8,
+) -> Result<()> {
     let mut watched_paths = HashSet::new();
 
     if let Err(e) = setup_watches(
-        &project_root,
+        project_root,
         &config,
         &mut debouncer,
         &mut watched_paths,
@@ -260,7 +351,7 @@ pub fn run_watch_mode(watch_args: WatchArgs, quiet: bool, verbose: u8) -> Result
                         }
 
                         let config_path_being_used_result = Config::resolve_config_path(
-                            &project_root,
+                            project_root,
                             watch_args.project_config.context_file.as_ref(),
                             watch_args.project_config.disable_context_file,
                         );
@@ -289,10 +380,10 @@ pub fn run_watch_mode(watch_args: WatchArgs, quiet: bool, verbose: u8) -> Result
                                 );
                             }
                             match load_config_for_command(
-                                &project_root,
+                                project_root,
                                 &watch_args.project_config,
                                 None,
-                                Some(&watch_args),
+                                Some(watch_args),
                                 None,
                             ) {
                                 Ok(reloaded_config) => {
@@ -302,7 +393,7 @@ pub fn run_watch_mode(watch_args: WatchArgs, quiet: bool, verbose: u8) -> Result
                                         eprintln!("{}", "✅ Configuration reloaded.".green());
                                     }
                                     if let Err(e) = setup_watches(
-                                        &project_root,
+                                        project_root,
                                         &config,
                                         &mut debouncer,
                                         &mut watched_paths,
@@ -337,15 +428,39 @@ pub fn run_watch_mode(watch_args: WatchArgs, quiet: bool, verbose: u8) -> Result
                             // Already printed message
                         }
 
+                        // A config reload can change every file's inclusion, so it gets a full context
+                        let changed_paths: Option<HashSet<PathBuf>> =
+                            (watch_args.delta && !config_reloaded).then(|| {
+                                debounced_events
+                                    .iter()
+                                    .map(|event| {
+                                        event
+                                            .path
+                                            .canonicalize()
+                                            .unwrap_or_else(|_| event.path.clone())
+                                    })
+                                    .collect()
+                            });
+
                         let output_target_args = OutputTargetArgs {
                             save: &watch_args.save,
                             chunks: &None,
                             stdout: watch_args.save.is_none(),
                             format_output: &watch_args.format_output,
+                            also_format: &[],
+                            no_banner: false,
+                            max_tokens: None,
+                            overview: false,
+                            manifest_only: false,
+                            docs_only: false,
+                            with_metrics: false,
+                            pretty_preview: false,
+                            sign: false,
+                            changed_paths: changed_paths.as_ref(),
                         };
 
                         if let Err(e) = generate::trigger_generation(
-                            &project_root,
+                            project_root,
                             &config,
                             &output_target_args,
                             quiet,
@@ -0,0 +1,64 @@
+use crate::error::Result;
+use crate::gather::{FileInfo, build_glob_set_from_vec};
+use crate::tokens::estimate_tokens;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone)]
+pub struct TokenBudgetOutcome {
+    pub kept: Vec<FileInfo>,
+    pub dropped: Vec<PathBuf>,
+    pub used_tokens: usize,
+}
+
+/// Keeps source files within `max_tokens`, considering `priority`-matched files first.
+///
+/// Files are taken greedily: priority matches in path order, then the rest in path
+/// order. A file that does not fit is dropped, but smaller files after it may still
+/// be kept. The kept files retain their original relative order.
+pub fn enforce_token_budget(
+    files: Vec<FileInfo>,
+    max_tokens: usize,
+    priority: &[String],
+    project_root: &Path,
+) -> Result<TokenBudgetOutcome> {
+    let priority_set = build_glob_set_from_vec(priority)?;
+
+    let mut candidates = Vec::with_capacity(files.len());
+    for (index, file) in files.iter().enumerate() {
+        let relative = file.path.strip_prefix(project_root).unwrap_or(&file.path);
+        let is_priority = priority_set.is_match(relative);
+        candidates.push((!is_priority, index, estimate_tokens(&file.content)?));
+    }
+    // Priority files sort first (false < true); the index keeps the order stable
+    candidates.sort_unstable_by_key(|&(non_priority, index, _)| (non_priority, index));
+
+    let mut keep = vec![false; files.len()];
+    let mut used_tokens = 0;
+    for (_, index, tokens) in candidates {
+        if used_tokens + tokens <= max_tokens {
+            used_tokens += tokens;
+            keep[index] = true;
+        }
+    }
+
+    let mut kept = Vec::new();
+    let mut dropped = Vec::new();
+    for (file, keep) in files.into_iter().zip(keep) {
+        if keep {
+            kept.push(file);
+        } else {
+            log::info!(
+                "Dropped {} to stay within the token budget of {}",
+                file.path.display(),
+                max_tokens
+            );
+            dropped.push(file.path);
+        }
+    }
+
+    Ok(TokenBudgetOutcome {
+        kept,
+        dropped,
+        used_tokens,
+    })
+}
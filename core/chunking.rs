@@ -1,8 +1,12 @@
 use crate::error::{AppError, Result};
 use crate::gather::FileInfo;
-use crate::output_formats::{ChunkFile, ChunkInfo, FileContextInfo};
+use crate::output_formats::{
+    ChunkFile, ChunkInfo, FileContextInfo, content_hash, relative_output_path,
+};
 use byte_unit::Byte;
 use log;
+#[cfg(feature = "serde_support")]
+use serde::Deserialize;
 use std::convert::TryInto;
 use std::path::Path;
 use std::str::FromStr;
@@ -11,6 +15,9 @@ pub fn split_files_into_chunks(
     source_files: Vec<FileInfo>,
     chunk_size_str: &str,
     project_root: &Path,
+    posix_paths: bool,
+    include_hashes: bool,
+    include_modes: bool,
 ) -> Result<Vec<ChunkFile>> {
     let byte_value = Byte::from_str(chunk_size_str).map_err(|e| {
         AppError::Chunking(format!(
@@ -36,10 +43,15 @@ pub fn split_files_into_chunks(
     let all_file_contexts: Vec<FileContextInfo> = source_files
         .into_iter()
         .map(|finfo| FileContextInfo {
-            path: pathdiff::diff_paths(&finfo.path, project_root)
-                .unwrap_or_else(|| finfo.path.clone())
-                .to_string_lossy()
-                .to_string(),
+            note: None,
+            package: None,
+            doc_type: None,
+            path: relative_output_path(&finfo.path, project_root, posix_paths),
+            hash: include_hashes.then(|| content_hash(&finfo.content)),
+            mode: finfo
+                .mode
+                .filter(|_| include_modes)
+                .map(|m| format!("{:o}", m)),
             content: finfo.content,
         })
         .collect();
@@ -115,3 +127,55 @@ pub fn split_files_into_chunks(
 
     Ok(final_chunks)
 }
+
+// Reads the multi-document YAML stream written when chunking with '-f yaml'
+#[cfg(feature = "serde_support")]
+pub fn parse_yaml_chunk_stream(content: &str) -> Result<Vec<ChunkFile>> {
+    serde_yml::Deserializer::from_str(content)
+        .map(|document| ChunkFile::deserialize(document).map_err(AppError::YamlError))
+        .collect()
+}
+
+// Reassembles chunks produced by `split_files_into_chunks`. Parts may arrive in any order but must
+// agree on the total and cover 1..=total exactly once.
+pub fn merge_chunks(mut chunks: Vec<ChunkFile>) -> Result<Vec<FileContextInfo>> {
+    let Some(total_parts) = chunks.first().map(|c| c.chunk_info.total_parts) else {
+        return Err(AppError::Chunking("No chunk files to merge".to_string()));
+    };
+    if let Some(mismatch) = chunks
+        .iter()
+        .find(|c| c.chunk_info.total_parts != total_parts)
+    {
+        return Err(AppError::Chunking(format!(
+            "Chunks disagree on the number of parts ({} vs {})",
+            total_parts, mismatch.chunk_info.total_parts
+        )));
+    }
+
+    chunks.sort_by_key(|c| c.chunk_info.current_part);
+    for (expected, chunk) in (1..=total_parts).zip(&chunks) {
+        let part = chunk.chunk_info.current_part;
+        if part == expected {
+            continue;
+        }
+        return Err(AppError::Chunking(if part < expected {
+            format!("Duplicate chunk part {}", part)
+        } else {
+            format!("Missing chunk part {} of {}", expected, total_parts)
+        }));
+    }
+    if chunks.len() != total_parts {
+        return Err(AppError::Chunking(if chunks.len() > total_parts {
+            format!(
+                "Found {} chunk files for {} parts (duplicate or stray parts)",
+                chunks.len(),
+                total_parts
+            )
+        } else {
+            format!("Missing chunk part {} of {}", chunks.len() + 1, total_parts)
+        }));
+    }
+
+    log::info!("Merging {} chunk parts.", total_parts);
+    Ok(chunks.into_iter().flat_map(|c| c.files).collect())
+}
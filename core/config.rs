@@ -1,5 +1,6 @@
 use crate::error::{AppError, Result};
 use crate::rules::{self, mapping as rules_mapping};
+use globset::Glob;
 use indexmap::IndexMap;
 use log;
 use parse_duration::parse;
@@ -7,13 +8,18 @@ use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fs;
-use std::path::{Path, PathBuf};
-use std::time::Duration;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
 
 pub const DEFAULT_CONFIG_DIR: &str = ".xtools/xcontext";
 pub const DEFAULT_CONFIG_FILENAME: &str = "xcontext.toml";
 pub const DEFAULT_CACHE_DIR: &str = ".xtools/xcontext/cache";
 pub const DEFAULT_WATCH_DELAY: &str = "300ms";
+pub const DEFAULT_POLL_INTERVAL: &str = "2s";
+pub const DEFAULT_MAX_LINE_LENGTH: usize = 1000;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(deny_unknown_fields)]
@@ -40,6 +46,8 @@ pub struct Config {
     pub save: SaveConfig,
     #[serde(default)]
     pub watch: WatchConfig,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub profiles: IndexMap<String, toml::Table>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -51,15 +59,37 @@ pub struct GeneralConfig {
     pub use_gitignore: bool,
     #[serde(default = "default_true")]
     pub enable_builtin_ignore: bool,
+    #[serde(default)]
+    pub read_retries: usize,
+    #[serde(default = "default_false")]
+    pub respect_dockerignore: bool,
+    #[serde(default = "default_false")]
+    pub derive_name_from_manifest: bool,
+    #[serde(default = "default_false")]
+    pub fail_on_empty: bool, // Error instead of emitting a context with no files
+    #[serde(default = "default_false")]
+    pub exclude_untracked: bool, // Drop files `git status` reports as untracked from source/docs
+    #[serde(default = "default_true")]
+    pub show_progress: bool, // Progress bar during walk/read; only drawn when stderr is a TTY
+    #[serde(default = "default_true")]
+    pub strict_root: bool, // Error when the project root can't be canonicalized; false falls back to the absolute path
+    #[serde(default = "default_false")]
+    pub include_editorconfig: bool, // Adds an `editorconfig` section summarizing the root .editorconfig
+    #[serde(skip)]
+    pub cli_ignore_files: Vec<PathBuf>, // --ignore-file sources, applied for this run only
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct CommonFiltersConfig {
     #[serde(default)]
     pub include: Vec<String>,
     #[serde(default)]
     pub exclude: Vec<String>,
+    #[serde(default = "default_false")]
+    pub exclude_generated: bool,
+    #[serde(default = "default_max_line_length")]
+    pub max_line_length: usize,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -81,6 +111,16 @@ pub struct DocsConfig {
     pub include: Option<Vec<String>>,
     #[serde(default)]
     pub exclude: Option<Vec<String>>,
+    #[serde(default = "default_false")]
+    pub directory_readmes: bool,
+    #[serde(default = "default_false")]
+    pub content_type: bool, // Tag each doc with a 'type' (readme, changelog, api-doc, ...) from its name
+    #[serde(default)]
+    pub max_files: Option<usize>, // Keep the first N docs (path order); the count of the rest is reported
+    #[serde(default)]
+    pub include_command: Option<String>,
+    #[serde(default)]
+    pub exclude_command: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -94,6 +134,12 @@ pub struct TreeConfig {
     pub include: Option<Vec<String>>,
     #[serde(default)]
     pub exclude: Option<Vec<String>>,
+    #[serde(default)]
+    pub dirs_first: bool,
+    #[serde(default)]
+    pub collapse: Vec<String>, // Directory globs shown as a single node, contents skipped
+    #[serde(default)]
+    pub prune_empty_dirs: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -107,6 +153,40 @@ pub struct SourceConfig {
     pub include: Option<Vec<String>>,
     #[serde(default)]
     pub exclude: Option<Vec<String>>,
+    #[serde(default)]
+    pub max_files_per_dir: Option<usize>,
+    #[serde(default)]
+    pub max_lines: Option<usize>, // Files with more lines are excluded entirely
+    #[serde(default)]
+    pub max_content_bytes: Option<usize>, // Longer files keep a prefix plus a truncation marker
+    #[serde(default)]
+    pub truncate_lines_over: Option<usize>, // Longer lines are cut (in chars) with a marker; line count kept
+    #[serde(default)]
+    pub max_tokens_per_file: Option<usize>, // Estimated tokens; see `max_tokens_strategy`
+    #[serde(default)]
+    pub max_tokens_strategy: TokenLimitStrategy, // What happens to files over `max_tokens_per_file`
+    #[serde(default)]
+    pub extract_headers: bool, // Adds an `overview` of each file's leading doc comment
+    #[serde(default)]
+    pub redact_env_values: bool, // `.env`/`.env.*` files keep keys and comments only
+    #[serde(default)]
+    pub priority: Vec<String>, // Globs kept first when a token budget is enforced
+    #[serde(default)]
+    pub order: Vec<String>, // Globs; inline files matching earlier patterns come first
+    #[serde(default)]
+    pub content_exclude: Vec<String>, // Globs kept in tree/index but emitted with placeholder content
+    #[serde(default)]
+    pub max_age: Option<String>, // e.g. "365d"; older files (by mtime) are excluded
+    #[serde(default)]
+    pub annotations: IndexMap<String, String>, // Path or glob -> note attached to matching files
+    #[serde(default)]
+    pub prepend_annotations: bool,
+    #[serde(default)]
+    pub transforms: IndexMap<String, ContentTransform>, // File extension (or "env") -> transform
+    #[serde(default)]
+    pub include_command: Option<String>, // Each stdout line becomes an include pattern
+    #[serde(default)]
+    pub exclude_command: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -119,6 +199,20 @@ pub struct RulesConfig {
     pub exclude: Vec<String>,
     #[serde(default)]
     pub import: Vec<PathBuf>,
+    #[serde(default)]
+    pub inline_max_bytes: Option<usize>, // Larger imports become a path reference
+    #[serde(default)]
+    pub inherit_ancestors: bool,
+    #[serde(default)]
+    pub normalize: bool, // Strip list markers, drop consecutive duplicate lines
+    #[serde(default)]
+    pub suppress_dynamic: Vec<String>, // Stems never auto-added from detected characteristics
+    #[serde(default)]
+    pub origin_order: Vec<String>, // e.g. ["custom", "imported", "static"]; see order_rulesets_by_origin
+    #[serde(default)]
+    pub scoped: IndexMap<String, Vec<String>>, // Glob -> static stems, loaded only if a gathered file matches
+    #[serde(default)]
+    pub line_exclude: Vec<String>, // Substrings, or regexes prefixed "re:"; matching rule lines are dropped
     #[serde(flatten)]
     pub custom: IndexMap<String, Vec<String>>,
 }
@@ -128,7 +222,7 @@ pub struct PromptsConfig {
     #[serde(default)]
     pub import: Vec<PathBuf>, // Changed to PathBuf
     #[serde(flatten, default)]
-    pub custom: HashMap<String, String>,
+    pub custom: IndexMap<String, String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -136,10 +230,16 @@ pub struct PromptsConfig {
 pub struct OutputConfig {
     #[serde(default = "default_format")]
     pub format: String,
+    #[serde(default)]
+    pub pretty: PrettyMode, // Applies to every format; `auto` defers to the flags below
     #[serde(default = "default_true")]
-    pub json_minify: bool,
+    pub json_minify: bool, // Deprecated in favour of `pretty`
     #[serde(default = "default_false")]
-    pub xml_pretty_print: bool, // Renamed for clarity, maps to !xml_minify
+    pub xml_pretty_print: bool, // Deprecated in favour of `pretty`
+    #[serde(default)]
+    pub xml_root: Option<String>, // Root element name for XML output; defaults to "ProjectContext"
+    #[serde(default)]
+    pub xml_namespace: Option<String>, // Emitted as `xmlns` on the XML root element
     #[serde(default = "default_true")]
     pub include_project_name: bool,
     #[serde(default = "default_true")]
@@ -148,6 +248,32 @@ pub struct OutputConfig {
     pub include_system_info: bool,
     #[serde(default = "default_true")]
     pub include_timestamp: bool,
+    #[serde(default = "default_true")]
+    pub posix_paths: bool,
+    #[serde(default = "default_false")]
+    pub include_workspaces: bool, // Also tags each emitted file with its owning member
+    #[serde(default = "default_false")]
+    pub include_git_info: bool, // Adds `git_info` with the default branch (origin/HEAD)
+    #[serde(default = "default_false")]
+    pub include_dependencies: bool, // Adds `dependencies` declared in root Cargo.toml/package.json/...
+    #[serde(default = "default_false")]
+    pub embed_config: bool, // Records the effective config (profiles already applied) in the output
+    #[serde(default = "default_false")]
+    pub include_legend: bool, // Adds `legend`: top-level field -> one-line description
+    #[serde(default = "default_false")]
+    pub include_file_index: bool,
+    #[serde(default = "default_false")]
+    pub include_file_hashes: bool, // Adds a content `hash` to every emitted file
+    #[serde(default = "default_false")]
+    pub include_file_mode: bool, // Adds the octal Unix `mode` (e.g. "100755") to every emitted file
+    #[serde(default = "default_false")]
+    pub prompts_as_list: bool, // Emit prompts as a name-sorted [{name, text}] array
+    #[serde(default = "default_true")]
+    pub trailing_newline: bool,
+    #[serde(default)]
+    pub ai_readme_format: AiReadmeFormat,
+    #[serde(default)]
+    pub sections: Vec<String>, // Top-level keys to emit, in order (e.g. ["tree", "source"]); empty = all
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -159,6 +285,14 @@ pub struct SaveConfig {
     pub filename_base: Option<String>,
     #[serde(default)]
     pub extension: Option<String>,
+    #[serde(default)]
+    pub write_manifest: bool,
+    #[serde(default)]
+    pub atomic_write: bool, // Write via temp file + rename so readers never see partial output
+    #[serde(default)]
+    pub chunk_filename_template: Option<String>, // Placeholders {base}, {n}, {total}, {ext}; {n} and a .{ext} ending are required
+    #[serde(skip)]
+    pub cli_save_path: Option<PathBuf>, // --save target, kept out of later walks
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -166,6 +300,12 @@ pub struct SaveConfig {
 pub struct WatchConfig {
     #[serde(default = "default_watch_delay_string")]
     pub delay: String,
+    #[serde(default = "default_false")]
+    pub force_poll: bool, // Poll instead of native events (NFS, Docker volumes)
+    #[serde(default = "default_poll_interval_string")]
+    pub poll_interval: String,
+    #[serde(default)]
+    pub initial_delay: Option<String>, // Wait before the first generation, e.g. "2s"
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -182,6 +322,51 @@ impl Default for IgnoreSetting {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PrettyMode {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl PrettyMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "always" => Some(Self::Always),
+            "never" => Some(Self::Never),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentTransform {
+    StripComments,
+    MinifyWhitespace,
+    RedactEnv,
+    None,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenLimitStrategy {
+    #[default]
+    Exclude, // Drop the file entirely
+    Truncate, // Keep the leading tokens plus a truncation marker
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AiReadmeFormat {
+    #[default]
+    Lines,
+    Paragraph,
+}
+
 fn default_true() -> bool {
     true
 }
@@ -191,12 +376,20 @@ fn default_false() -> bool {
 fn default_format() -> String {
     "json".to_string()
 }
+fn default_max_line_length() -> usize {
+    DEFAULT_MAX_LINE_LENGTH
+}
+const DEFAULT_CHUNK_FILENAME_TEMPLATE: &str = "{base}_chunk_{n}.{ext}";
+
 fn default_save_dir_config() -> PathBuf {
     PathBuf::from(DEFAULT_CACHE_DIR)
 }
 fn default_watch_delay_string() -> String {
     DEFAULT_WATCH_DELAY.to_string()
 }
+fn default_poll_interval_string() -> String {
+    DEFAULT_POLL_INTERVAL.to_string()
+}
 
 impl Default for Config {
     fn default() -> Self {
@@ -212,6 +405,7 @@ impl Default for Config {
             output: OutputConfig::default(),
             save: SaveConfig::default(),
             watch: WatchConfig::default(),
+            profiles: IndexMap::new(),
         }
     }
 }
@@ -221,6 +415,25 @@ impl Default for GeneralConfig {
             project_name: None,
             use_gitignore: default_true(),
             enable_builtin_ignore: default_true(),
+            read_retries: 0,
+            respect_dockerignore: default_false(),
+            derive_name_from_manifest: default_false(),
+            fail_on_empty: default_false(),
+            exclude_untracked: default_false(),
+            show_progress: default_true(),
+            strict_root: default_true(),
+            include_editorconfig: default_false(),
+            cli_ignore_files: Vec::new(),
+        }
+    }
+}
+impl Default for CommonFiltersConfig {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: Vec::new(),
+            exclude_generated: default_false(),
+            max_line_length: default_max_line_length(),
         }
     }
 }
@@ -241,6 +454,11 @@ impl Default for DocsConfig {
             use_gitignore: IgnoreSetting::default(),
             include: Some(Vec::new()),
             exclude: Some(Vec::new()),
+            directory_readmes: default_false(),
+            content_type: default_false(),
+            max_files: None,
+            include_command: None,
+            exclude_command: None,
         }
     }
 }
@@ -251,6 +469,9 @@ impl Default for TreeConfig {
             use_gitignore: IgnoreSetting::default(),
             include: Some(Vec::new()),
             exclude: Some(Vec::new()),
+            dirs_first: default_false(),
+            collapse: Vec::new(),
+            prune_empty_dirs: default_false(),
         }
     }
 }
@@ -261,6 +482,23 @@ impl Default for SourceConfig {
             use_gitignore: IgnoreSetting::default(),
             include: Some(Vec::new()),
             exclude: Some(Vec::new()),
+            max_files_per_dir: None,
+            max_lines: None,
+            max_content_bytes: None,
+            truncate_lines_over: None,
+            max_tokens_per_file: None,
+            max_tokens_strategy: TokenLimitStrategy::default(),
+            extract_headers: default_false(),
+            redact_env_values: default_false(),
+            priority: Vec::new(),
+            order: Vec::new(),
+            content_exclude: Vec::new(),
+            max_age: None,
+            annotations: IndexMap::new(),
+            prepend_annotations: default_false(),
+            transforms: IndexMap::new(),
+            include_command: None,
+            exclude_command: None,
         }
     }
 }
@@ -271,6 +509,13 @@ impl Default for RulesConfig {
             include: Vec::new(),
             exclude: Vec::new(),
             import: Vec::new(),
+            inline_max_bytes: None,
+            inherit_ancestors: default_false(),
+            normalize: default_false(),
+            suppress_dynamic: Vec::new(),
+            origin_order: Vec::new(),
+            scoped: IndexMap::new(),
+            line_exclude: Vec::new(),
             custom: IndexMap::new(),
         }
     }
@@ -280,21 +525,57 @@ impl Default for OutputConfig {
     fn default() -> Self {
         Self {
             format: default_format(),
+            pretty: PrettyMode::default(),
             json_minify: default_true(),
             xml_pretty_print: default_false(),
+            xml_root: None,
+            xml_namespace: None,
             include_project_name: default_true(),
             include_project_root: default_true(),
             include_system_info: default_true(),
             include_timestamp: default_true(),
+            posix_paths: default_true(),
+            include_workspaces: default_false(),
+            include_git_info: default_false(),
+            include_dependencies: default_false(),
+            embed_config: default_false(),
+            include_legend: default_false(),
+            include_file_index: default_false(),
+            include_file_hashes: default_false(),
+            include_file_mode: default_false(),
+            prompts_as_list: default_false(),
+            trailing_newline: default_true(),
+            ai_readme_format: AiReadmeFormat::default(),
+            sections: Vec::new(),
+        }
+    }
+}
+impl OutputConfig {
+    // `auto` keeps the legacy per-format behaviour: JSON follows json_minify, XML follows
+    // xml_pretty_print, and YAML is always block style.
+    pub fn pretty_for(&self, format: &str) -> bool {
+        match self.pretty {
+            PrettyMode::Always => true,
+            PrettyMode::Never => false,
+            PrettyMode::Auto => match format.to_lowercase().as_str() {
+                "json" => !self.json_minify,
+                "xml" => self.xml_pretty_print,
+                _ => true,
+            },
         }
     }
 }
+
 impl Default for SaveConfig {
     fn default() -> Self {
         Self {
             output_dir: default_save_dir_config(),
             filename_base: None,
             extension: None,
+            write_manifest: default_false(),
+            atomic_write: default_false(),
+            chunk_filename_template: None,
+            cli_save_path: None,
         }
     }
 }
@@ -302,6 +583,9 @@ impl Default for WatchConfig {
     fn default() -> Self {
         Self {
             delay: default_watch_delay_string(),
+            force_poll: default_false(),
+            poll_interval: default_poll_interval_string(),
+            initial_delay: None,
         }
     }
 }
@@ -334,16 +618,35 @@ impl Config {
             None => env::current_dir().map_err(AppError::Io)?,
         };
 
-        path_to_resolve.canonicalize().map_err(|e| {
-            AppError::Io(std::io::Error::new(
-                e.kind(),
+        let canonicalize_error = match path_to_resolve.canonicalize() {
+            Ok(canonical) => return Ok(canonical),
+            Err(e) => e,
+        };
+
+        // The root's own config decides whether a non-canonical root is acceptable
+        let absolute = normalize_absolute_path(&path_to_resolve)?;
+        let strict = Self::resolve_config_path(&absolute, None, false)
+            .ok()
+            .flatten()
+            .and_then(|config_path| Self::load_from_path(&config_path).ok())
+            .is_none_or(|config| config.general.strict_root);
+        if strict {
+            return Err(AppError::Io(std::io::Error::new(
+                canonicalize_error.kind(),
                 format!(
                     "Failed to canonicalize project root '{}': {}",
                     path_to_resolve.display(),
-                    e
+                    canonicalize_error
                 ),
-            ))
-        })
+            )));
+        }
+        log::warn!(
+            "Failed to canonicalize project root '{}' ({}); using '{}' because general.strict_root is false.",
+            path_to_resolve.display(),
+            canonicalize_error,
+            absolute.display()
+        );
+        Ok(absolute)
     }
 
     pub fn resolve_config_path(
@@ -431,6 +734,87 @@ impl Config {
         })
     }
 
+    pub fn apply_profile(self, profile_name: &str) -> Result<Config> {
+        let overrides = self.profiles.get(profile_name).cloned().ok_or_else(|| {
+            let available: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            AppError::Config(format!(
+                "Unknown profile '{}'. Available profiles: {}",
+                profile_name,
+                if available.is_empty() {
+                    "(none defined)".to_string()
+                } else {
+                    available.join(", ")
+                }
+            ))
+        })?;
+        log::debug!("Applying config profile '{}'", profile_name);
+
+        let mut merged = toml::Table::try_from(&self).map_err(|e| {
+            AppError::Config(format!("Failed to prepare config for profile merge: {}", e))
+        })?;
+        merge_toml_tables(&mut merged, overrides);
+        toml::Value::Table(merged)
+            .try_into::<Config>()
+            .map_err(|e| {
+                AppError::TomlParse(format!(
+                    "Error applying profile '{}': {}. Check the [profiles.{}] overrides.",
+                    profile_name, e, profile_name
+                ))
+            })
+    }
+
+    // Applies `KEY.PATH=VALUE` assignments (from `--set`) on top of the loaded config. Values are
+    // read as TOML (`false`, `3`, `["a"]`) and fall back to a plain string; keys the schema does not
+    // know are rejected.
+    pub fn apply_overrides(self, assignments: &[String]) -> Result<Config> {
+        let mut merged = toml::Table::try_from(&self).map_err(|e| {
+            AppError::Config(format!("Failed to prepare config for overrides: {}", e))
+        })?;
+        let mut assigned_paths = Vec::new();
+        for assignment in assignments {
+            let (path, raw_value) = assignment.split_once('=').ok_or_else(|| {
+                AppError::InvalidArgument(format!(
+                    "Invalid --set '{}': expected KEY.PATH=VALUE, e.g. output.format=yaml",
+                    assignment
+                ))
+            })?;
+            let keys: Vec<&str> = path.trim().split('.').map(str::trim).collect();
+            if keys.iter().any(|key| key.is_empty()) {
+                return Err(AppError::InvalidArgument(format!(
+                    "Invalid --set '{}': empty key in '{}'",
+                    assignment, path
+                )));
+            }
+            log::debug!("Applying config override {}", assignment);
+            let nested = keys
+                .iter()
+                .rev()
+                .fold(parse_override_value(raw_value.trim()), |inner, key| {
+                    toml::Value::Table(toml::Table::from_iter([(key.to_string(), inner)]))
+                });
+            if let toml::Value::Table(overrides) = nested {
+                merge_toml_tables(&mut merged, overrides);
+            }
+            assigned_paths.push((assignment, keys));
+        }
+
+        let config: Config = toml::Value::Table(merged)
+            .try_into()
+            .map_err(|e| AppError::TomlParse(format!("Error applying --set overrides: {}", e)))?;
+        // Sections without `deny_unknown_fields` drop unknown keys silently, so check what stuck
+        let applied = toml::Table::try_from(&config)
+            .map_err(|e| AppError::Config(format!("Failed to verify config overrides: {}", e)))?;
+        for (assignment, keys) in assigned_paths {
+            if !table_has_path(&applied, &keys) {
+                return Err(AppError::InvalidArgument(format!(
+                    "Unknown config key in --set '{}'",
+                    assignment
+                )));
+            }
+        }
+        Ok(config)
+    }
+
     pub fn get_watch_delay(&self) -> Result<Duration> {
         parse(&self.watch.delay).map_err(|e| {
             AppError::InvalidArgument(format!(
@@ -440,6 +824,127 @@ impl Config {
         })
     }
 
+    pub fn get_poll_interval(&self) -> Result<Duration> {
+        parse(&self.watch.poll_interval).map_err(|e| {
+            AppError::InvalidArgument(format!(
+                "Invalid watch poll interval '{}': {}. Use format like '500ms', '2s'.",
+                self.watch.poll_interval, e
+            ))
+        })
+    }
+
+    pub fn get_initial_delay(&self) -> Result<Option<Duration>> {
+        self.watch
+            .initial_delay
+            .as_deref()
+            .map(|delay| {
+                parse(delay).map_err(|e| {
+                    AppError::InvalidArgument(format!(
+                        "Invalid watch initial delay '{}': {}. Use format like '500ms', '2s'.",
+                        delay, e
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    /// Name of chunk `n` of `total`: `save.chunk_filename_template` with its placeholders filled
+    /// in, or `{base}_chunk_{n}.{ext}` when unset.
+    pub fn chunk_filename(&self, base: &str, n: usize, total: usize, ext: &str) -> Result<String> {
+        let template = self
+            .save
+            .chunk_filename_template
+            .as_deref()
+            .unwrap_or(DEFAULT_CHUNK_FILENAME_TEMPLATE);
+        let invalid = |reason: &str| {
+            AppError::InvalidArgument(format!(
+                "Invalid save.chunk_filename_template '{}': {}",
+                template, reason
+            ))
+        };
+        if !template.contains("{n}") {
+            return Err(invalid(
+                "it must contain {n} so every chunk gets its own file",
+            ));
+        }
+        if template.contains(['/', '\\']) {
+            return Err(invalid("it must be a file name, not a path"));
+        }
+        // `merge` only picks up '*.json' files, so the name must keep its extension
+        if !template.ends_with(".{ext}") && !template.ends_with(&format!(".{}", ext)) {
+            return Err(invalid(&format!("it must end in .{{ext}} or .{}", ext)));
+        }
+        let fill = |base: &str, n: &str, total: &str, ext: &str| {
+            template
+                .replace("{base}", base)
+                .replace("{n}", n)
+                .replace("{total}", total)
+                .replace("{ext}", ext)
+        };
+        // Braces left over once the known placeholders are gone mean a misspelled placeholder
+        if fill("", "", "", "").contains(['{', '}']) {
+            return Err(invalid(
+                "unknown placeholder; use {base}, {n}, {total} or {ext}",
+            ));
+        }
+        Ok(fill(base, &n.to_string(), &total.to_string(), ext))
+    }
+
+    /// Runs the configured `include_command`/`exclude_command` of the source and docs
+    /// sections and appends each non-empty output line to that section's patterns.
+    pub fn resolve_filter_commands(&mut self, project_root: &Path) -> Result<()> {
+        let common_include = self.common_filters.include.clone();
+        let common_exclude = self.common_filters.exclude.clone();
+        let sections = [
+            (
+                &self.source.include_command,
+                &mut self.source.include,
+                &common_include,
+            ),
+            (
+                &self.source.exclude_command,
+                &mut self.source.exclude,
+                &common_exclude,
+            ),
+            (
+                &self.docs.include_command,
+                &mut self.docs.include,
+                &common_include,
+            ),
+            (
+                &self.docs.exclude_command,
+                &mut self.docs.exclude,
+                &common_exclude,
+            ),
+        ];
+        for (command, patterns, common) in sections {
+            let Some(command) = command else {
+                continue;
+            };
+            let lines = run_filter_command(command, project_root)?;
+            log::debug!(
+                "Filter command '{}' produced {} patterns",
+                command,
+                lines.len()
+            );
+            // A section without its own patterns inherits the common ones, so keep them
+            patterns.get_or_insert_with(|| common.clone()).extend(lines);
+        }
+        Ok(())
+    }
+
+    pub fn get_source_max_age(&self) -> Result<Option<Duration>> {
+        let Some(max_age) = &self.source.max_age else {
+            return Ok(None);
+        };
+        parse(max_age).map(Some).map_err(|e| {
+            AppError::InvalidArgument(format!(
+                "Invalid source max_age duration '{}': {}. Use format like '90d', '52w'.",
+                max_age, e
+            ))
+        })
+    }
+
     pub fn get_effective_gitignore(&self, section_setting: &IgnoreSetting) -> bool {
         match section_setting {
             IgnoreSetting::True => true,
@@ -458,24 +963,214 @@ impl Config {
 
     pub fn get_effective_project_name(&self, project_root: &Path) -> String {
         self.general.project_name.clone().unwrap_or_else(|| {
-            project_root
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
+            self.general
+                .derive_name_from_manifest
+                .then(|| find_manifest_project_name(project_root))
+                .flatten()
+                .or_else(|| {
+                    project_root
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                })
                 .unwrap_or_else(|| "UnknownProject".to_string())
         })
     }
 }
 
+// Walks up from the project root to the nearest Cargo.toml `[package].name` or package.json `name`.
+fn find_manifest_project_name(project_root: &Path) -> Option<String> {
+    project_root
+        .ancestors()
+        .find_map(|dir| {
+            let cargo_name = fs::read_to_string(dir.join("Cargo.toml"))
+                .ok()
+                .and_then(|content| content.parse::<toml::Table>().ok())
+                .and_then(|manifest| {
+                    manifest
+                        .get("package")?
+                        .get("name")?
+                        .as_str()
+                        .map(str::to_string)
+                });
+            cargo_name.or_else(|| {
+                fs::read_to_string(dir.join("package.json"))
+                    .ok()
+                    .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+                    .and_then(|manifest| manifest.get("name")?.as_str().map(str::to_string))
+            })
+        })
+        .filter(|name| !name.trim().is_empty())
+}
+
+// Lexically absolutizes `path` (resolving `.` and `..` without touching the filesystem)
+fn normalize_absolute_path(path: &Path) -> Result<PathBuf> {
+    let absolute = std::path::absolute(path).map_err(AppError::Io)?;
+    let mut normalized = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    Ok(normalized)
+}
+
+// Deep-merges `overrides` into `base`: nested tables merge key by key, other values replace.
+fn merge_toml_tables(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, override_value) in overrides {
+        match (base.get_mut(&key), override_value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(override_table)) => {
+                merge_toml_tables(base_table, override_table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+// Parses a `--set` value as TOML, falling back to a plain string when it is not valid TOML
+fn parse_override_value(raw: &str) -> toml::Value {
+    format!("value = {}", raw)
+        .parse::<toml::Table>()
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(raw.to_string()))
+}
+
+// Whether `keys` names an existing entry (a table or a value) within `table`
+fn table_has_path(table: &toml::Table, keys: &[&str]) -> bool {
+    match keys {
+        [] => true,
+        [key, rest @ ..] => match table.get(*key) {
+            Some(toml::Value::Table(inner)) => table_has_path(inner, rest),
+            Some(_) => rest.is_empty(),
+            None => false,
+        },
+    }
+}
+
 #[derive(Debug, Default, Clone, Serialize)]
 pub struct ResolvedRules {
     pub rulesets: IndexMap<String, Vec<String>>,
     pub origins: HashMap<String, String>,
 }
 
+const FILTER_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+fn run_filter_command(command: &str, project_root: &Path) -> Result<Vec<String>> {
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+    let mut child = Command::new(shell)
+        .args([flag, command])
+        .current_dir(project_root)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| AppError::FilterCommand(format!("Failed to run '{}': {}", command, e)))?;
+
+    // Drain the pipes on threads so a chatty command cannot block on a full pipe
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+    let stdout_reader = thread::spawn(move || {
+        let mut out = String::new();
+        stdout.read_to_string(&mut out).map(|_| out)
+    });
+    let stderr_reader = thread::spawn(move || {
+        let mut err = String::new();
+        let _ = stderr.read_to_string(&mut err);
+        err
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() > FILTER_COMMAND_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(AppError::FilterCommand(format!(
+                "'{}' timed out after {:?}",
+                command, FILTER_COMMAND_TIMEOUT
+            )));
+        }
+        thread::sleep(Duration::from_millis(50));
+    };
+
+    let output = stdout_reader.join().map_err(|_| {
+        AppError::FilterCommand(format!("Failed to read output of '{}'", command))
+    })??;
+    let errors = stderr_reader.join().unwrap_or_default();
+    if !status.success() {
+        return Err(AppError::FilterCommand(format!(
+            "'{}' exited with {}: {}",
+            command,
+            status,
+            errors.trim()
+        )));
+    }
+
+    Ok(output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+// Groups rulesets by origin in `origin_order`, sorted by key within each group. An entry matches
+// a key prefix ("static", "imported", "inherited", "custom") or a recorded origin such as
+// "dynamic" or "import-ref". Rulesets matching no entry keep their order after the groups.
+fn order_rulesets_by_origin(resolved: &mut ResolvedRules, origin_order: &[String]) {
+    let mut remaining = std::mem::take(&mut resolved.rulesets);
+    for origin in origin_order {
+        let mut group: Vec<(String, Vec<String>)> = Vec::new();
+        remaining.retain(|key, rules| {
+            let prefix = key.split(':').next().unwrap_or_default();
+            let recorded = resolved.origins.get(key).map(String::as_str);
+            if prefix == origin || recorded == Some(origin.as_str()) {
+                group.push((key.clone(), std::mem::take(rules)));
+                false
+            } else {
+                true
+            }
+        });
+        group.sort_by(|a, b| a.0.cmp(&b.0));
+        resolved.rulesets.extend(group);
+    }
+    resolved.rulesets.extend(remaining);
+}
+
+/// Finds a rule or prompt import relative to the project root, then the config directory.
+pub fn resolve_import_path(project_root: &Path, import_path_rel: &Path) -> Option<PathBuf> {
+    let import_path = project_root.join(import_path_rel);
+    if import_path.exists() {
+        return Some(import_path);
+    }
+    let import_path = project_root.join(DEFAULT_CONFIG_DIR).join(import_path_rel);
+    if import_path.exists() {
+        log::trace!(
+            "Found import {} relative to config dir",
+            import_path_rel.display()
+        );
+        return Some(import_path);
+    }
+    None
+}
+
+// `gathered_paths` feeds `[rules.scoped]`; callers that haven't gathered files pass an empty slice
 pub fn resolve_rules(
     rules_config: &RulesConfig,
     project_root: &Path,
     project_characteristics: &HashSet<String>,
+    gathered_paths: &[&Path],
 ) -> Result<ResolvedRules> {
     let mut resolved = ResolvedRules::default();
     if !rules_config.enabled {
@@ -492,7 +1187,13 @@ pub fn resolve_rules(
         if let Some(stem) = rules_mapping::map_characteristic_to_rule_stem(&char_lower)
             .or_else(|| rules_mapping::map_characteristic_to_rule_stem(char))
         {
-            if base_static_stems.insert(stem) {
+            if rules_config.suppress_dynamic.iter().any(|s| s == stem) {
+                log::trace!(
+                    "Suppressed dynamic rule stem '{}' from characteristic '{}'",
+                    stem,
+                    char
+                );
+            } else if base_static_stems.insert(stem) {
                 log::trace!(
                     "Dynamically detected rule stem '{}' from characteristic '{}'",
                     stem,
@@ -506,7 +1207,11 @@ pub fn resolve_rules(
         base_static_stems
     );
 
-    let exclude_stems: HashSet<&str> = rules_config.exclude.iter().map(String::as_str).collect();
+    let exclude_stems: HashSet<&str> = rules_config
+        .exclude
+        .iter()
+        .map(|stem| rules::normalize_static_rule_stem(stem))
+        .collect();
     if !exclude_stems.is_empty() {
         log::debug!("Applying rule exclusions: {:?}", exclude_stems);
     }
@@ -520,13 +1225,42 @@ pub fn resolve_rules(
         effective_static_stems
     );
 
-    let include_stems: HashSet<&str> = rules_config.include.iter().map(String::as_str).collect();
+    let include_stems: HashSet<&str> = rules_config
+        .include
+        .iter()
+        .map(|stem| rules::normalize_static_rule_stem(stem))
+        .collect();
     if !include_stems.is_empty() {
         log::debug!("Applying explicit rule inclusions: {:?}", include_stems);
     }
     for stem in include_stems.iter() {
         effective_static_stems.insert(stem);
     }
+
+    // Stem -> the scope glob that pulled it in, for stems not already selected otherwise
+    let mut scoped_stems: HashMap<&str, &str> = HashMap::new();
+    for (pattern, stems) in &rules_config.scoped {
+        let matcher = Glob::new(pattern)?.compile_matcher();
+        let triggered = gathered_paths
+            .iter()
+            .any(|path| matcher.is_match(path.strip_prefix(project_root).unwrap_or(path)));
+        if !triggered {
+            log::trace!("Rule scope '{}' matched no gathered files", pattern);
+            continue;
+        }
+        for stem in stems
+            .iter()
+            .map(|stem| rules::normalize_static_rule_stem(stem))
+        {
+            if exclude_stems.contains(stem) {
+                continue;
+            }
+            if effective_static_stems.insert(stem) {
+                log::trace!("Scoped rule stem '{}' triggered by '{}'", stem, pattern);
+                scoped_stems.insert(stem, pattern.as_str());
+            }
+        }
+    }
     log::debug!("Final static stems to load: {:?}", effective_static_stems);
 
     for stem in effective_static_stems.iter() {
@@ -545,13 +1279,15 @@ pub fn resolve_rules(
                 let origin = match (
                     rules_mapping::get_default_rule_stems().contains(stem),
                     include_stems.contains(stem),
+                    scoped_stems.get(stem),
                 ) {
-                    (true, true) => "default+include",
-                    (true, false) => "default",
-                    (false, true) => "include",
-                    (false, false) => "dynamic",
+                    (true, true, _) => "default+include".to_string(),
+                    (true, false, _) => "default".to_string(),
+                    (false, true, _) => "include".to_string(),
+                    (false, false, Some(scope)) => format!("scoped:{}", scope),
+                    (false, false, None) => "dynamic".to_string(),
                 };
-                resolved.origins.insert(key.clone(), origin.to_string());
+                resolved.origins.insert(key.clone(), origin.clone());
                 log::trace!("Loaded static rule: {} (Origin: {})", key, origin);
             }
             Err(e) => {
@@ -564,29 +1300,43 @@ pub fn resolve_rules(
         log::debug!("Loading imported rules from: {:?}", rules_config.import);
     }
     for import_path_rel in &rules_config.import {
-        let mut import_path = project_root.join(import_path_rel);
-        if !import_path.exists() {
-            let config_dir = project_root.join(DEFAULT_CONFIG_DIR);
-            import_path = config_dir.join(import_path_rel);
-            if import_path.exists() {
-                log::trace!(
-                    "Found import {} relative to config dir",
-                    import_path_rel.display()
-                );
-            } else {
-                log::warn!(
-                    "Could not find imported rule file '{}' relative to project root or config dir. Skipping.",
-                    import_path_rel.display()
-                );
-                continue;
-            }
-        }
+        let Some(import_path) = resolve_import_path(project_root, import_path_rel) else {
+            log::warn!(
+                "Could not find imported rule file '{}' relative to project root or config dir. Skipping.",
+                import_path_rel.display()
+            );
+            continue;
+        };
 
         let stem = import_path
             .file_stem()
             .and_then(|s| s.to_str())
             .unwrap_or("imported_rule");
         let key = format!("imported:{}", stem);
+
+        let file_size = fs::metadata(&import_path).map(|m| m.len()).unwrap_or(0);
+        if rules_config
+            .inline_max_bytes
+            .is_some_and(|max_bytes| file_size > max_bytes as u64)
+        {
+            let display_path = pathdiff::diff_paths(&import_path, project_root)
+                .unwrap_or_else(|| import_path.clone());
+            resolved.rulesets.insert(
+                key.clone(),
+                vec![format!(
+                    "See rule file: {} ({} bytes, not inlined)",
+                    display_path.display(),
+                    file_size
+                )],
+            );
+            resolved.origins.insert(key, "import-ref".to_string());
+            log::trace!(
+                "Referenced imported rule without inlining: {}",
+                import_path.display()
+            );
+            continue;
+        }
+
         match fs::read_to_string(&import_path) {
             Ok(content) => {
                 resolved.rulesets.insert(
@@ -611,6 +1361,10 @@ pub fn resolve_rules(
         }
     }
 
+    if rules_config.inherit_ancestors {
+        load_inherited_rules(project_root, &mut resolved);
+    }
+
     if !rules_config.custom.is_empty() {
         log::debug!(
             "Loading custom rules defined in config: {:?}",
@@ -636,38 +1390,172 @@ pub fn resolve_rules(
         resolved.origins.insert(key.clone(), "custom".to_string());
         log::trace!("Loaded custom rule set: {}", name);
     }
+    if !rules_config.line_exclude.is_empty() {
+        exclude_rule_lines(&mut resolved, &rules_config.line_exclude)?;
+    }
+    if rules_config.normalize {
+        for rules_list in resolved.rulesets.values_mut() {
+            *rules_list = normalize_rule_lines(std::mem::take(rules_list));
+        }
+    }
+    if !rules_config.origin_order.is_empty() {
+        order_rulesets_by_origin(&mut resolved, &rules_config.origin_order);
+    }
     log::info!("Resolved {} rulesets.", resolved.rulesets.len());
     Ok(resolved)
 }
 
+// Drops every loaded rule line matching one of `rules.line_exclude`: a plain substring, or a
+// regex when prefixed with "re:"
+fn exclude_rule_lines(resolved: &mut ResolvedRules, patterns: &[String]) -> Result<()> {
+    enum LineMatcher<'a> {
+        Substring(&'a str),
+        Regex(regex::Regex),
+    }
+    let matchers = patterns
+        .iter()
+        .map(|pattern| match pattern.strip_prefix("re:") {
+            Some(expr) => regex::Regex::new(expr)
+                .map(LineMatcher::Regex)
+                .map_err(|e| {
+                    AppError::Config(format!(
+                        "Invalid rules.line_exclude regex '{}': {}",
+                        expr, e
+                    ))
+                }),
+            None => Ok(LineMatcher::Substring(pattern)),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    for (key, lines) in resolved.rulesets.iter_mut() {
+        let before = lines.len();
+        lines.retain(|line| {
+            !matchers.iter().any(|matcher| match matcher {
+                LineMatcher::Substring(text) => line.contains(text),
+                LineMatcher::Regex(re) => re.is_match(line),
+            })
+        });
+        if lines.len() < before {
+            log::trace!(
+                "Removed {} lines from '{}' (rules.line_exclude)",
+                before - lines.len(),
+                key
+            );
+        }
+    }
+    Ok(())
+}
+
+// Strips a leading list marker ("- ", "* ", "+ ", "1. ", "1) ") and drops lines identical to the previous one.
+fn normalize_rule_lines(lines: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(lines.len());
+    for line in lines {
+        let stripped = strip_list_marker(&line).trim();
+        if stripped.is_empty() || normalized.last().is_some_and(|prev| prev == stripped) {
+            continue;
+        }
+        normalized.push(stripped.to_string());
+    }
+    normalized
+}
+
+fn strip_list_marker(line: &str) -> &str {
+    if let Some(rest) = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|marker| line.strip_prefix(marker))
+    {
+        return rest;
+    }
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits > 0 {
+        let rest = &line[digits..];
+        if let Some(rest) = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") ")) {
+            return rest;
+        }
+    }
+    line
+}
+
+// Merges rule files from every `.xtools/xcontext/rules/` between the filesystem root and the
+// project root. Farther directories load first, so a closer file with the same stem replaces it.
+fn load_inherited_rules(project_root: &Path, resolved: &mut ResolvedRules) {
+    let ancestors: Vec<&Path> = project_root.ancestors().collect();
+    for dir in ancestors.into_iter().rev() {
+        let rules_dir = dir.join(DEFAULT_CONFIG_DIR).join("rules");
+        let Ok(entries) = fs::read_dir(&rules_dir) else {
+            continue;
+        };
+        let mut rule_files: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file())
+            .collect();
+        rule_files.sort();
+
+        let display_dir =
+            pathdiff::diff_paths(&rules_dir, project_root).unwrap_or_else(|| rules_dir.clone());
+        let origin = format!("inherited:{}", display_dir.display());
+        log::debug!(
+            "Loading {} inherited rule files from {}",
+            rule_files.len(),
+            rules_dir.display()
+        );
+
+        for rule_path in rule_files {
+            let Some(stem) = rule_path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let key = format!("inherited:{}", stem);
+            match fs::read_to_string(&rule_path) {
+                Ok(content) => {
+                    resolved.rulesets.insert(
+                        key.clone(),
+                        content
+                            .lines()
+                            .map(str::trim)
+                            .filter(|s| !s.is_empty())
+                            .map(String::from)
+                            .collect(),
+                    );
+                    resolved.origins.insert(key, origin.clone());
+                    log::trace!("Loaded inherited rule: {}", rule_path.display());
+                }
+                Err(e) => {
+                    log::warn!(
+                        "Failed to read inherited rule file '{}': {}",
+                        rule_path.display(),
+                        e
+                    );
+                }
+            }
+        }
+    }
+}
+
 pub fn resolve_prompts(
     prompts_config: &PromptsConfig,
     project_root: &Path,
-) -> Result<HashMap<String, String>> {
-    let mut resolved = HashMap::new();
+) -> Result<IndexMap<String, String>> {
+    let mut resolved = IndexMap::new();
     let predefined = crate::output_formats::get_predefined_prompts(); // Assuming get_predefined_prompts moved here
+    let mut static_names: Vec<&String> = predefined.keys().collect();
+    static_names.sort(); // The embedded map is unordered; keep output stable
     resolved.extend(
-        predefined
-            .iter()
-            .map(|(k, v)| (format!("static:{}", k), v.clone())),
+        static_names
+            .into_iter()
+            .map(|k| (format!("static:{}", k), predefined[k].clone())),
     );
 
     if !prompts_config.import.is_empty() {
         log::debug!("Loading imported prompts from: {:?}", prompts_config.import);
     }
     for import_path_rel in &prompts_config.import {
-        let mut import_path = project_root.join(import_path_rel);
-        if !import_path.exists() {
-            let config_dir = project_root.join(DEFAULT_CONFIG_DIR);
-            import_path = config_dir.join(import_path_rel);
-            if !import_path.exists() {
-                log::warn!(
-                    "Could not find imported prompt file '{}' relative to project root or config dir. Skipping.",
-                    import_path_rel.display()
-                );
-                continue;
-            }
-        }
+        let Some(import_path) = resolve_import_path(project_root, import_path_rel) else {
+            log::warn!(
+                "Could not find imported prompt file '{}' relative to project root or config dir. Skipping.",
+                import_path_rel.display()
+            );
+            continue;
+        };
 
         let stem = import_path
             .file_stem()
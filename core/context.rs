@@ -1,17 +1,52 @@
 use crate::config::{self, Config, ResolvedRules};
+use crate::dependencies::ManifestDependencies;
+use crate::editorconfig::EditorConfigSection;
 use crate::error::Result;
 use crate::gather::{self, TreeNode}; // Corrected: Use gather::TreeNode
+use crate::headers;
+use crate::tokens;
 // Removed unused import: use crate::output_formats::AiReadmeText;
-use crate::output_formats::{FileContextInfo, SourceRepresentation, get_ai_readme_text};
+use crate::output_formats::{
+    ChunkFile, ExtensionMetrics, FileContextInfo, FileOverview, GitInfo, MetricsSummary,
+    PromptsSection, SourceRepresentation, content_hash, get_ai_readme_text, relative_output_path,
+};
 use crate::system::SystemInfo;
 use chrono::{DateTime, Utc};
+use globset::{Glob, GlobMatcher};
 use indexmap::IndexMap;
 use log;
 #[cfg(feature = "serde_support")]
 use serde::Serialize;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
+const OMITTED_CONTENT_PLACEHOLDER: &str = "[content omitted]";
+
+// Serialized top-level keys of `ProjectContext`, the names accepted by `output.sections`
+pub const OUTPUT_SECTIONS: &[&str] = &[
+    "aiReadme",
+    "legend",
+    "projectName",
+    "projectRoot",
+    "systemInfo",
+    "meta",
+    "fileIndex",
+    "overview",
+    "metrics",
+    "docs",
+    "docsOmitted",
+    "tree",
+    "workspaces",
+    "gitInfo",
+    "dependencies",
+    "editorconfig",
+    "source",
+    "rules",
+    "prompts",
+    "config",
+    "generationTimestamp",
+];
+
 #[derive(Debug, Clone, Default)]
 #[cfg_attr(feature = "serde_support", derive(Serialize))]
 #[cfg_attr(feature = "serde_support", serde(rename_all = "camelCase"))] // Or snake_case
@@ -25,6 +60,11 @@ pub struct ProjectContext {
         feature = "serde_support",
         serde(skip_serializing_if = "Option::is_none")
     )]
+    pub legend: Option<IndexMap<String, String>>,
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(skip_serializing_if = "Option::is_none")
+    )]
     pub project_name: Option<String>,
     #[cfg_attr(
         feature = "serde_support",
@@ -45,16 +85,56 @@ pub struct ProjectContext {
         feature = "serde_support",
         serde(skip_serializing_if = "Option::is_none")
     )]
+    pub file_index: Option<Vec<String>>,
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub overview: Option<Vec<FileOverview>>,
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub metrics: Option<MetricsSummary>,
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(skip_serializing_if = "Option::is_none")
+    )]
     pub docs: Option<Vec<FileContextInfo>>,
     #[cfg_attr(
         feature = "serde_support",
         serde(skip_serializing_if = "Option::is_none")
     )]
+    pub docs_omitted: Option<usize>, // Docs dropped by docs.max_files; still listed in the tree
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(skip_serializing_if = "Option::is_none")
+    )]
     pub tree: Option<Vec<TreeNode>>, // This uses gather::TreeNode now
     #[cfg_attr(
         feature = "serde_support",
         serde(skip_serializing_if = "Option::is_none")
     )]
+    pub workspaces: Option<Vec<String>>,
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub git_info: Option<GitInfo>,
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub dependencies: Option<Vec<ManifestDependencies>>,
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub editorconfig: Option<Vec<EditorConfigSection>>,
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(skip_serializing_if = "Option::is_none")
+    )]
     pub source: Option<SourceRepresentation>,
     #[cfg_attr(
         feature = "serde_support",
@@ -65,7 +145,12 @@ pub struct ProjectContext {
         feature = "serde_support",
         serde(skip_serializing_if = "Option::is_none")
     )]
-    pub prompts: Option<HashMap<String, String>>,
+    pub prompts: Option<PromptsSection>,
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub config: Option<Config>, // Effective config, with output.embed_config
     #[cfg_attr(
         feature = "serde_support",
         serde(skip_serializing_if = "Option::is_none")
@@ -78,11 +163,110 @@ pub struct ProjectContext {
 }
 
 impl ProjectContext {
+    // The top-level sections named in `sections`, in that order. Unlisted sections are dropped and
+    // the readme and legend rebuilt to describe only what is emitted; listed sections that are
+    // absent (disabled or empty) are skipped with a warning
+    #[cfg(feature = "serde_support")]
+    pub fn ordered_sections(
+        &self,
+        sections: &[String],
+        config: &Config,
+    ) -> Result<IndexMap<String, serde_json::Value>> {
+        let listed = |name: &str| sections.iter().any(|s| s == name);
+        let mut kept = self.clone();
+        kept.drop_unlisted_sections(listed);
+        let mut readme_config = config.clone();
+        readme_config.source.enabled &= listed("source");
+        readme_config.rules.enabled &= listed("rules");
+        readme_config.output.include_legend &= listed("legend");
+        kept.populate_ai_readme(&readme_config);
+
+        let mut fields = match serde_json::to_value(&kept)? {
+            serde_json::Value::Object(fields) => fields,
+            _ => serde_json::Map::new(),
+        };
+        for missing in sections.iter().filter(|name| {
+            OUTPUT_SECTIONS.contains(&name.as_str()) && !fields.contains_key(name.as_str())
+        }) {
+            log::warn!(
+                "Section '{}' in output.sections is disabled or empty, so it is left out.",
+                missing
+            );
+        }
+        Ok(sections
+            .iter()
+            .filter_map(|name| fields.remove(name).map(|value| (name.clone(), value)))
+            .collect())
+    }
+
+    // Clears every section `listed` rejects; the readme and legend are left to the caller
+    #[cfg(feature = "serde_support")]
+    fn drop_unlisted_sections(&mut self, listed: impl Fn(&str) -> bool) {
+        if !listed("projectName") {
+            self.project_name = None;
+        }
+        if !listed("projectRoot") {
+            self.project_root = None;
+        }
+        if !listed("systemInfo") {
+            self.system_info = None;
+        }
+        if !listed("meta") {
+            self.meta = None;
+        }
+        if !listed("fileIndex") {
+            self.file_index = None;
+        }
+        if !listed("overview") {
+            self.overview = None;
+        }
+        if !listed("metrics") {
+            self.metrics = None;
+        }
+        if !listed("docs") {
+            self.docs = None;
+        }
+        if !listed("docsOmitted") {
+            self.docs_omitted = None;
+        }
+        if !listed("tree") {
+            self.tree = None;
+        }
+        if !listed("workspaces") {
+            self.workspaces = None;
+        }
+        if !listed("gitInfo") {
+            self.git_info = None;
+        }
+        if !listed("dependencies") {
+            self.dependencies = None;
+        }
+        if !listed("editorconfig") {
+            self.editorconfig = None;
+        }
+        if !listed("source") {
+            self.source = None;
+        }
+        if !listed("prompts") {
+            self.prompts = None;
+        }
+        if !listed("config") {
+            self.config = None;
+        }
+        if !listed("generationTimestamp") {
+            self.generation_timestamp = None;
+        }
+        if !listed("rules") {
+            self.rules.clear();
+        }
+    }
+
     pub fn build(
         project_root_path: &Path,
         config: &Config,
         tree_structure: Option<Vec<TreeNode>>,
         project_characteristics: &HashSet<String>,
+        gathered_paths: &[&Path], // Drives [rules.scoped]
     ) -> Result<Self> {
         log::debug!("Building project context skeleton...");
 
@@ -103,20 +287,57 @@ impl ProjectContext {
         };
 
         log::trace!("Resolving rules...");
-        let resolved_rules =
-            config::resolve_rules(&config.rules, project_root_path, project_characteristics)?;
+        let resolved_rules = config::resolve_rules(
+            &config.rules,
+            project_root_path,
+            project_characteristics,
+            gathered_paths,
+        )?;
         let resolved_rules_debug_info = resolved_rules.clone();
         log::trace!("Rules resolved.");
 
         log::trace!("Resolving prompts...");
         let prompts_section = match config::resolve_prompts(&config.prompts, project_root_path) {
-            Ok(map) if !map.is_empty() => Some(map),
+            Ok(map) if !map.is_empty() => {
+                Some(PromptsSection::new(map, config.output.prompts_as_list))
+            }
             _ => None,
         };
         log::trace!("Prompts resolved.");
 
+        let workspaces = if config.output.include_workspaces {
+            log::trace!("Detecting workspace members...");
+            let members = crate::rules::detect_workspace_members(project_root_path);
+            (!members.is_empty()).then_some(members)
+        } else {
+            None
+        };
+
+        let git_info = if config.output.include_git_info {
+            log::trace!("Detecting git metadata...");
+            crate::git::detect_default_branch(project_root_path)
+                .map(|default_branch| GitInfo { default_branch })
+        } else {
+            None
+        };
+
+        let dependencies = if config.output.include_dependencies {
+            log::trace!("Collecting manifest dependencies...");
+            crate::dependencies::collect_dependencies(project_root_path, project_characteristics)
+        } else {
+            None
+        };
+
+        let editorconfig = if config.general.include_editorconfig {
+            log::trace!("Summarizing .editorconfig...");
+            crate::editorconfig::summarize_editorconfig(project_root_path)
+        } else {
+            None
+        };
+
         let mut context = ProjectContext {
             ai_readme: None, // Will be populated later
+            legend: None,
             project_name: if config.output.include_project_name {
                 Some(config.get_effective_project_name(project_root_path))
             } else {
@@ -129,15 +350,27 @@ impl ProjectContext {
             },
             system_info: sys_info,
             meta: meta_map,
-            docs: None, // Populated by add_docs
+            file_index: None, // Populated by add_files and add_docs
+            overview: None,   // Populated by add_overview
+            metrics: None,    // Populated by add_metrics_summary
+            docs: None,       // Populated by add_docs
+            docs_omitted: None,
             tree: if config.tree.enabled {
                 tree_structure
             } else {
                 None
             },
+            workspaces,
+            git_info,
+            dependencies,
+            editorconfig,
             source: None, // Populated by add_files or add_chunk_paths
             rules: resolved_rules.rulesets,
             prompts: prompts_section,
+            config: config.output.embed_config.then(|| Config {
+                profiles: IndexMap::new(), // Already merged in; the rest would only add noise
+                ..config.clone()
+            }),
             generation_timestamp: if config.output.include_timestamp {
                 Some(Utc::now())
             } else {
@@ -155,19 +388,165 @@ impl ProjectContext {
     fn create_file_context_list(
         files_info: Vec<gather::FileInfo>,
         project_root: &Path,
+        output_config: &config::OutputConfig,
     ) -> Vec<FileContextInfo> {
         files_info
             .into_iter()
             .map(|finfo| FileContextInfo {
-                path: pathdiff::diff_paths(&finfo.path, project_root)
-                    .unwrap_or_else(|| finfo.path.clone()) // Fallback to absolute if diff fails
-                    .to_string_lossy()
-                    .to_string(),
+                // Falls back to the absolute path if diffing fails
+                path: relative_output_path(&finfo.path, project_root, output_config.posix_paths),
+                hash: output_config
+                    .include_file_hashes
+                    .then(|| content_hash(&finfo.content)),
+                mode: finfo
+                    .mode
+                    .filter(|_| output_config.include_file_mode)
+                    .map(|m| format!("{:o}", m)),
                 content: finfo.content,
+                note: None,
+                package: None,
+                doc_type: None,
             })
             .collect()
     }
 
+    // Tags each file with the deepest workspace member directory containing it
+    pub fn tag_packages(files: &mut [FileContextInfo], members: &[String]) {
+        for file in files {
+            file.package = members
+                .iter()
+                .filter(|member| Path::new(&file.path).starts_with(member.as_str()))
+                .max_by_key(|member| member.len())
+                .cloned();
+        }
+    }
+
+    // Compiles `pattern`, warning (and returning None) when it is not a valid glob
+    fn compile_matcher(pattern: &str, kind: &str) -> Option<GlobMatcher> {
+        match Glob::new(pattern) {
+            Ok(glob) => Some(glob.compile_matcher()),
+            Err(e) => {
+                log::warn!("Ignoring invalid {} pattern '{}': {}", kind, pattern, e);
+                None
+            }
+        }
+    }
+
+    // Matchers for every valid pattern, in order; invalid ones are warned about and skipped
+    fn build_matchers(patterns: &[String], kind: &str) -> Vec<GlobMatcher> {
+        patterns
+            .iter()
+            .filter_map(|pattern| Self::compile_matcher(pattern, kind))
+            .collect()
+    }
+
+    // Stable sort by the first `source.order` pattern each file matches; unmatched files go last
+    fn apply_explicit_order(files: &mut [FileContextInfo], patterns: &[String]) {
+        let matchers = Self::build_matchers(patterns, "order");
+        files.sort_by_cached_key(|file| {
+            matchers
+                .iter()
+                .position(|m| m.is_match(&file.path))
+                .unwrap_or(matchers.len())
+        });
+    }
+
+    // Attaches the first matching `[source.annotations]` note to each file
+    pub fn apply_annotations(files: &mut [FileContextInfo], source_config: &config::SourceConfig) {
+        let matchers: Vec<(GlobMatcher, &String)> = source_config
+            .annotations
+            .iter()
+            .filter_map(|(pattern, note)| {
+                Self::compile_matcher(pattern, "annotation").map(|m| (m, note))
+            })
+            .collect();
+
+        for file in files.iter_mut() {
+            let Some((_, note)) = matchers.iter().find(|(m, _)| m.is_match(&file.path)) else {
+                continue;
+            };
+            if source_config.prepend_annotations {
+                file.content = format!("Note: {}\n\n{}", note, file.content);
+                if file.hash.is_some() {
+                    file.hash = Some(content_hash(&file.content)); // Hash what is emitted
+                }
+            }
+            file.note = Some(note.to_string());
+        }
+    }
+
+    // Replaces the content of files matching `source.content_exclude` with a placeholder
+    fn omit_excluded_content(files: &mut [FileContextInfo], patterns: &[String]) {
+        let matchers = Self::build_matchers(patterns, "content_exclude");
+        for file in files
+            .iter_mut()
+            .filter(|file| matchers.iter().any(|m| m.is_match(&file.path)))
+        {
+            log::trace!("Omitting content of {}", file.path);
+            file.content = OMITTED_CONTENT_PLACEHOLDER.to_string();
+            if file.hash.is_some() {
+                file.hash = Some(content_hash(&file.content)); // Hash what is emitted
+            }
+        }
+    }
+
+    // Keeps the first `max_per_dir` files (in sort order) of each immediate parent directory
+    fn limit_files_per_dir(
+        files_info: Vec<gather::FileInfo>,
+        max_per_dir: usize,
+        project_root: &Path,
+        posix_paths: bool,
+    ) -> (Vec<gather::FileInfo>, IndexMap<String, usize>) {
+        let mut kept_per_dir: HashMap<PathBuf, usize> = HashMap::new();
+        let mut omitted: IndexMap<String, usize> = IndexMap::new();
+        let mut kept = Vec::with_capacity(files_info.len());
+
+        for finfo in files_info {
+            let parent = finfo.path.parent().unwrap_or(project_root).to_path_buf();
+            let count = kept_per_dir.entry(parent.clone()).or_insert(0);
+            if *count < max_per_dir {
+                *count += 1;
+                kept.push(finfo);
+            } else {
+                let mut dir_key = relative_output_path(&parent, project_root, posix_paths);
+                if dir_key.is_empty() {
+                    dir_key = ".".to_string();
+                }
+                *omitted.entry(dir_key).or_insert(0) += 1;
+            }
+        }
+
+        for (dir, count) in &omitted {
+            log::info!(
+                "Omitted {} files from '{}' (max_files_per_dir = {})",
+                count,
+                dir,
+                max_per_dir
+            );
+        }
+        (kept, omitted)
+    }
+
+    // Applies `source.max_files_per_dir`, for inline and chunked sources alike
+    pub fn apply_max_files_per_dir(
+        files_info: Vec<gather::FileInfo>,
+        project_root: &Path,
+        config: &Config,
+    ) -> (Vec<gather::FileInfo>, Option<IndexMap<String, usize>>) {
+        match config.source.max_files_per_dir {
+            Some(max_per_dir) => {
+                let (kept, omitted) = Self::limit_files_per_dir(
+                    files_info,
+                    max_per_dir,
+                    project_root,
+                    config.output.posix_paths,
+                );
+                (kept, (!omitted.is_empty()).then_some(omitted))
+            }
+            None => (files_info, None),
+        }
+    }
+
     pub fn add_files(
         mut self,
         source_files_info: Vec<gather::FileInfo>,
@@ -175,16 +554,30 @@ impl ProjectContext {
         config: &Config, // Needed to repopulate readme
     ) -> Self {
         if config.source.enabled && !source_files_info.is_empty() {
+            let (source_files_info, omitted_per_dir) =
+                Self::apply_max_files_per_dir(source_files_info, project_root, config);
             log::debug!(
                 "Adding {} source files inline to context.",
                 source_files_info.len()
             );
+            let mut files =
+                Self::create_file_context_list(source_files_info, project_root, &config.output);
+            if !config.source.order.is_empty() {
+                Self::apply_explicit_order(&mut files, &config.source.order);
+            }
+            if !config.source.content_exclude.is_empty() {
+                Self::omit_excluded_content(&mut files, &config.source.content_exclude);
+            }
+            if !config.source.annotations.is_empty() {
+                Self::apply_annotations(&mut files, &config.source);
+            }
+            if let Some(members) = &self.workspaces {
+                Self::tag_packages(&mut files, members);
+            }
             self.source = Some(SourceRepresentation {
-                files: Some(Self::create_file_context_list(
-                    source_files_info,
-                    project_root,
-                )),
+                files: Some(files),
                 chunks: None,
+                omitted_per_dir,
             });
         } else if config.source.enabled {
             log::debug!("No source files provided or found to add inline.");
@@ -193,25 +586,124 @@ impl ProjectContext {
             log::debug!("Source section disabled, not adding files.");
             self.source = None;
         }
+        self.refresh_file_index(&[], config);
         self.populate_ai_readme(config); // Repopulate after potentially changing source
         self
     }
 
+    // Flat, sorted list of every source (inline or chunked) and docs path
+    fn refresh_file_index(&mut self, chunks: &[ChunkFile], config: &Config) {
+        if !config.output.include_file_index {
+            return;
+        }
+        let source_files = self.source.as_ref().and_then(|s| s.files.as_ref());
+        let mut index: Vec<String> = source_files
+            .into_iter()
+            .chain(self.docs.as_ref())
+            .flatten()
+            .chain(chunks.iter().flat_map(|chunk| &chunk.files))
+            .map(|f| f.path.clone())
+            .collect();
+        index.sort();
+        index.dedup();
+        self.file_index = (!index.is_empty()).then_some(index);
+    }
+
+    // Summarizes each source file by its leading doc/header comment; works with inline or chunked sources
+    pub fn add_overview(
+        mut self,
+        source_files_info: &[gather::FileInfo],
+        project_root: &Path,
+        config: &Config,
+    ) -> Self {
+        if !config.source.extract_headers {
+            return self;
+        }
+        let overview: Vec<FileOverview> = source_files_info
+            .iter()
+            .filter_map(|finfo| {
+                let summary = headers::extract_header_summary(&finfo.path, &finfo.content)?;
+                Some(FileOverview {
+                    path: relative_output_path(
+                        &finfo.path,
+                        project_root,
+                        config.output.posix_paths,
+                    ),
+                    summary,
+                })
+            })
+            .collect();
+        log::debug!("Extracted header summaries for {} files.", overview.len());
+        self.overview = (!overview.is_empty()).then_some(overview);
+        self.populate_ai_readme(config);
+        self
+    }
+
+    // Summarizes size and token counts in place of file contents (generate --overview)
+    pub fn add_metrics_summary(
+        mut self,
+        files_info: &[gather::FileInfo],
+        config: &Config,
+    ) -> Result<Self> {
+        let mut summary = MetricsSummary::default();
+        let mut by_extension: BTreeMap<String, ExtensionMetrics> = BTreeMap::new();
+        for finfo in files_info {
+            let lines = finfo.content.lines().count();
+            let tokens = tokens::estimate_tokens(&finfo.content)?;
+            let extension = finfo
+                .path
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_else(|| "(none)".to_string());
+            let entry = by_extension.entry(extension).or_default();
+            entry.files += 1;
+            entry.lines += lines;
+            entry.bytes += finfo.size;
+            entry.estimated_tokens += tokens;
+            summary.total_files += 1;
+            summary.total_lines += lines;
+            summary.total_bytes += finfo.size;
+            summary.estimated_tokens += tokens;
+        }
+        summary.by_extension = by_extension.into_iter().collect();
+        log::debug!("Summarized metrics for {} files.", summary.total_files);
+        self.metrics = Some(summary);
+        self.populate_ai_readme(config);
+        Ok(self)
+    }
+
     pub fn add_docs(
         mut self,
         docs_files_info: Vec<gather::FileInfo>,
         project_root: &Path,
         config: &Config, // Needed to repopulate readme
     ) -> Self {
+        self.docs_omitted = None;
         if config.docs.enabled && !docs_files_info.is_empty() {
+            let mut docs_files_info = docs_files_info;
+            if let Some(max_files) = config.docs.max_files
+                && docs_files_info.len() > max_files
+            {
+                let omitted = docs_files_info.len() - max_files;
+                docs_files_info.truncate(max_files); // Gathered docs are already path-sorted
+                log::info!("Omitted {} docs files (max_files = {})", omitted, max_files);
+                self.docs_omitted = Some(omitted);
+            }
             log::debug!(
                 "Adding {} documentation files to context.",
                 docs_files_info.len()
             );
-            self.docs = Some(Self::create_file_context_list(
-                docs_files_info,
-                project_root,
-            ));
+            let mut files =
+                Self::create_file_context_list(docs_files_info, project_root, &config.output);
+            if let Some(members) = &self.workspaces {
+                Self::tag_packages(&mut files, members);
+            }
+            if config.docs.content_type {
+                for file in files.iter_mut() {
+                    file.doc_type = Some(classify_doc_type(&file.path).to_string());
+                }
+            }
+            self.docs = Some(files);
         } else if config.docs.enabled {
             log::debug!("No documentation files provided or found.");
             self.docs = None;
@@ -219,6 +711,7 @@ impl ProjectContext {
             log::debug!("Docs section disabled, not adding files.");
             self.docs = None;
         }
+        self.refresh_file_index(&[], config);
         self.populate_ai_readme(config); // Repopulate after potentially changing docs
         self
     }
@@ -226,6 +719,8 @@ impl ProjectContext {
     pub fn add_chunk_paths(
         mut self,
         chunk_paths: Vec<PathBuf>,
+        chunks: &[ChunkFile], // Their files are listed in the file index
+        omitted_per_dir: Option<IndexMap<String, usize>>,
         save_dir: &Path,
         config: &Config, // Needed to repopulate readme
     ) -> Self {
@@ -238,14 +733,13 @@ impl ProjectContext {
                 .into_iter()
                 .map(|p| {
                     // Try to make path relative to save_dir, fallback to original path string
-                    pathdiff::diff_paths(&p, save_dir)
-                        .map(|rel_p| rel_p.to_string_lossy().to_string())
-                        .unwrap_or_else(|| p.to_string_lossy().to_string())
+                    relative_output_path(&p, save_dir, config.output.posix_paths)
                 })
                 .collect();
             self.source = Some(SourceRepresentation {
                 files: None,
                 chunks: Some(relative_chunk_paths),
+                omitted_per_dir,
             });
         } else if config.source.enabled {
             log::debug!("No chunk paths provided.");
@@ -255,6 +749,7 @@ impl ProjectContext {
             log::debug!("Source section disabled, not adding chunk paths.");
             self.source = None;
         }
+        self.refresh_file_index(chunks, config);
         self.populate_ai_readme(config); // Repopulate after potentially changing source representation
         self
     }
@@ -277,12 +772,36 @@ impl ProjectContext {
         if self.meta.is_some() {
             details.push(&readme_template.meta_desc);
         }
+        if self.file_index.is_some() {
+            details.push(&readme_template.file_index_desc);
+        }
+        if self.overview.is_some() {
+            details.push(&readme_template.overview_desc);
+        }
+        if self.metrics.is_some() {
+            details.push(&readme_template.metrics_desc);
+        }
         if self.docs.is_some() {
             details.push(&readme_template.docs_desc);
         }
+        if self.docs_omitted.is_some() {
+            details.push(&readme_template.docs_omitted_desc);
+        }
         if self.tree.is_some() {
             details.push(&readme_template.tree_desc);
         }
+        if self.workspaces.is_some() {
+            details.push(&readme_template.workspaces_desc);
+        }
+        if self.git_info.is_some() {
+            details.push(&readme_template.git_info_desc);
+        }
+        if self.dependencies.is_some() {
+            details.push(&readme_template.dependencies_desc);
+        }
+        if self.editorconfig.is_some() {
+            details.push(&readme_template.editorconfig_desc);
+        }
 
         if let Some(source_repr) = &self.source {
             if source_repr.files.is_some() {
@@ -293,7 +812,7 @@ impl ProjectContext {
                 // Should not happen if source is Some, but handle defensively
                 details.push(&readme_template.source_missing_desc);
             }
-        } else if config.source.enabled {
+        } else if config.source.enabled && self.metrics.is_none() {
             // Source enabled but no files/chunks added yet or found
             details.push(&readme_template.source_missing_desc);
         }
@@ -307,14 +826,33 @@ impl ProjectContext {
         }
         // No else needed if rules are disabled
 
+        if self.config.is_some() {
+            details.push(&readme_template.config_desc);
+        }
         if self.generation_timestamp.is_some() {
             details.push(&readme_template.timestamp_desc);
         }
+        if config.output.include_legend {
+            details.push(&readme_template.legend_desc);
+            self.legend = Some(
+                std::iter::once(readme_template.ai_readme_desc.as_str())
+                    .chain(details.iter().copied())
+                    .filter_map(legend_entry)
+                    .collect(),
+            );
+        }
 
         if !details.is_empty() {
             parts.push(&readme_template.key_sections_header);
             parts.extend(details);
-            self.ai_readme = Some(parts.join("\\n"));
+            self.ai_readme = Some(match config.output.ai_readme_format {
+                config::AiReadmeFormat::Lines => parts.join("\n"),
+                config::AiReadmeFormat::Paragraph => parts
+                    .iter()
+                    .map(|part| part.trim())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            });
         } else {
             // Fallback if somehow no sections are included
             self.ai_readme = Some(readme_template.intro.clone());
@@ -322,3 +860,115 @@ impl ProjectContext {
         log::trace!("AI Readme populated.");
     }
 }
+
+// Splits a "- 'field': description" readme line into a legend entry keyed by the serialized
+// (camelCase) field name; "(Not included ...)" lines describe absent fields and are skipped
+fn legend_entry(desc: &str) -> Option<(String, String)> {
+    let (field, description) = desc.trim().strip_prefix("- '")?.split_once("': ")?;
+    if description.starts_with("(Not included") {
+        return None;
+    }
+    let mut key = String::with_capacity(field.len());
+    let mut upper_next = false;
+    for c in field.chars() {
+        match c {
+            '_' => upper_next = true,
+            _ if upper_next => {
+                key.extend(c.to_uppercase());
+                upper_next = false;
+            }
+            _ => key.push(c),
+        }
+    }
+    Some((key, description.to_string()))
+}
+
+// Filename heuristics for the kind of documentation a file holds; `path` is the emitted relative path
+fn classify_doc_type(path: &str) -> &'static str {
+    let path = Path::new(path);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let in_dir = |names: &[&str]| {
+        path.parent().is_some_and(|parent| {
+            parent
+                .components()
+                .any(|c| names.contains(&c.as_os_str().to_string_lossy().to_lowercase().as_str()))
+        })
+    };
+
+    match stem.as_str() {
+        "readme" => "readme",
+        "changelog" | "changes" | "history" | "news" | "releases" | "release-notes" => "changelog",
+        "license" | "licence" | "copying" | "notice" => "license",
+        "contributing" | "code_of_conduct" | "code-of-conduct" | "security" | "governance" => {
+            "contributing"
+        }
+        _ if in_dir(&["adr", "adrs", "decisions"]) => "adr",
+        _ if in_dir(&["api", "reference"]) || stem.starts_with("api") => "api-doc",
+        _ if in_dir(&["docs", "doc", "guide", "guides", "tutorials"]) => "guide",
+        _ => "doc",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn readme_for(format: config::AiReadmeFormat) -> String {
+        let mut config = Config::default();
+        config.output.ai_readme_format = format;
+        config.output.include_legend = true;
+        let mut context = ProjectContext {
+            project_name: Some("demo".to_string()),
+            project_root: Some("/tmp/demo".to_string()),
+            meta: Some(HashMap::new()),
+            docs: Some(Vec::new()),
+            tree: Some(Vec::new()),
+            generation_timestamp: Some(Utc::now()),
+            ..ProjectContext::default()
+        };
+        context.populate_ai_readme(&config);
+        context.ai_readme.expect("ai readme is populated")
+    }
+
+    #[test]
+    fn ai_readme_has_no_literal_newline_escapes() {
+        for format in [
+            config::AiReadmeFormat::Lines,
+            config::AiReadmeFormat::Paragraph,
+        ] {
+            let readme = readme_for(format);
+            assert!(
+                !readme.contains("\\n"),
+                "{:?} readme contains a literal \\n: {}",
+                format,
+                readme
+            );
+        }
+    }
+
+    #[test]
+    fn lines_format_uses_real_newlines() {
+        assert!(readme_for(config::AiReadmeFormat::Lines).contains('\n'));
+    }
+
+    #[test]
+    fn ordered_sections_describe_only_what_is_emitted() {
+        let mut config = Config::default();
+        config.output.include_legend = true;
+        let mut context = ProjectContext {
+            project_name: Some("demo".to_string()),
+            tree: Some(Vec::new()),
+            ..ProjectContext::default()
+        };
+        context.populate_ai_readme(&config);
+        let sections: Vec<String> = ["legend", "tree", "gitInfo"].map(String::from).to_vec();
+        let ordered = context.ordered_sections(&sections, &config).unwrap();
+
+        assert_eq!(ordered.keys().collect::<Vec<_>>(), ["legend", "tree"]);
+        let legend = ordered["legend"].as_object().unwrap();
+        assert!(legend.contains_key("tree") && !legend.contains_key("projectName"));
+    }
+}
@@ -1,19 +1,41 @@
+pub mod budget;
 pub mod chunking;
 pub mod config;
 pub mod context;
+pub mod dependencies;
+pub mod editorconfig;
 pub mod error;
 pub mod gather;
+pub mod git;
+pub mod headers;
+pub mod languages;
 pub mod output_formats;
 pub mod rules;
 pub mod system;
+pub mod tokens;
+pub mod transforms;
+
+// Cargo features this build of the core library was compiled with
+pub const COMPILED_FEATURES: &[&str] = &[
+    #[cfg(feature = "serde_support")]
+    "serde_support",
+];
 
 pub use config::{Config, MetaConfig, PromptsConfig, ResolvedRules, RulesConfig};
-pub use context::ProjectContext;
+pub use context::{OUTPUT_SECTIONS, ProjectContext};
+pub use dependencies::ManifestDependencies;
+pub use editorconfig::EditorConfigSection;
 pub use error::{AppError, Result};
-pub use gather::{FileInfo, TreeNode, gather_files_and_tree}; // Ensure TreeNode is re-exported
+pub use gather::{
+    FileInfo, FilterDecision, GatherResult, PathExplanation, TreeNode, explain_path,
+    gather_files_and_tree,
+}; // Ensure TreeNode is re-exported
+pub use languages::markdown_language_for_path;
 pub use output_formats::{
-    AiReadmeText, BuiltinIgnores, ChunkFile, ChunkInfo, FileContextInfo, SourceRepresentation,
+    AiReadmeText, BuiltinIgnores, ChunkFile, ChunkInfo, ContentManifest, ExtensionMetrics,
+    FileContextInfo, FileOverview, GitInfo, PromptEntry, PromptsSection, SourceRepresentation,
     TextType, get_ai_readme_text, get_builtin_ignore_patterns, get_predefined_text,
+    serialize_context_to_markdown, serialize_docs_bundle,
 };
-pub use rules::{detect_project_characteristics, get_static_rule_content};
+pub use rules::{detect_project_characteristics, get_static_rule_content, list_static_rules};
 pub use system::{SystemInfo, gather_system_info};
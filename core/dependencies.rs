@@ -0,0 +1,314 @@
+use indexmap::IndexMap;
+#[cfg(feature = "serde_support")]
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+// Version shown for entries that declare no requirement (e.g. a bare `requests` line)
+const ANY_VERSION: &str = "*";
+
+#[derive(Debug, Default, PartialEq)]
+struct ParsedManifest {
+    dependencies: IndexMap<String, String>,
+    dev_dependencies: IndexMap<String, String>,
+    peer_dependencies: IndexMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize))]
+#[cfg_attr(feature = "serde_support", serde(rename_all = "camelCase"))]
+pub struct ManifestDependencies {
+    pub manifest: String,  // Relative to the project root, e.g. "Cargo.toml"
+    pub ecosystem: String, // "cargo", "npm", "pip" or "go"
+    pub dependencies: IndexMap<String, String>, // Name -> declared version requirement
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(skip_serializing_if = "IndexMap::is_empty")
+    )]
+    pub dev_dependencies: IndexMap<String, String>,
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(skip_serializing_if = "IndexMap::is_empty")
+    )]
+    pub peer_dependencies: IndexMap<String, String>, // npm peerDependencies, provided by the host
+}
+
+// Reads the root manifests of the ecosystems found by `detect_project_characteristics` and
+// lists their declared external dependencies, one entry per manifest
+pub fn collect_dependencies(
+    project_root: &Path,
+    characteristics: &HashSet<String>,
+) -> Option<Vec<ManifestDependencies>> {
+    type Parser = fn(&str) -> Option<ParsedManifest>;
+    let manifests: [(&str, &str, Parser); 4] = [
+        ("Cargo.toml", "cargo", parse_cargo_toml),
+        ("package.json", "npm", parse_package_json),
+        ("requirements.txt", "pip", parse_requirements_txt),
+        ("go.mod", "go", parse_go_mod),
+    ];
+
+    let mut found = Vec::new();
+    for (manifest, ecosystem, parse) in manifests {
+        // requirements.txt only shows up as a "txt" extension, so its presence is checked directly
+        if manifest != "requirements.txt" && !characteristics.contains(manifest) {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(project_root.join(manifest)) else {
+            continue;
+        };
+        match parse(&content) {
+            Some(parsed) => found.push(ManifestDependencies {
+                manifest: manifest.to_string(),
+                ecosystem: ecosystem.to_string(),
+                dependencies: parsed.dependencies,
+                dev_dependencies: parsed.dev_dependencies,
+                peer_dependencies: parsed.peer_dependencies,
+            }),
+            None => log::warn!("Could not parse {} for dependencies; skipping.", manifest),
+        }
+    }
+    log::debug!("Collected dependencies from {} manifests", found.len());
+    (!found.is_empty()).then_some(found)
+}
+
+// Root, `[workspace.dependencies]` and `[target.*]` tables; path crates are workspace-internal and
+// `{ workspace = true }` entries take the workspace's requirement
+fn parse_cargo_toml(content: &str) -> Option<ParsedManifest> {
+    let manifest: toml::Table = content.parse().ok()?;
+    let workspace_specs = manifest
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table);
+    let section = |table: Option<&toml::Value>| -> IndexMap<String, String> {
+        table
+            .and_then(toml::Value::as_table)
+            .into_iter()
+            .flatten()
+            .filter_map(|(name, spec)| {
+                let spec = match spec.get("workspace").and_then(toml::Value::as_bool) {
+                    Some(true) => workspace_specs.and_then(|w| w.get(name)).unwrap_or(spec),
+                    _ => spec,
+                };
+                spec.get("path")
+                    .is_none()
+                    .then(|| (name.clone(), cargo_requirement(spec)))
+            })
+            .collect()
+    };
+    let targets: Vec<&toml::Value> = manifest
+        .get("target")
+        .and_then(toml::Value::as_table)
+        .into_iter()
+        .flat_map(|targets| targets.values())
+        .collect();
+    let with_targets = |key: &str| {
+        let mut dependencies = section(manifest.get(key));
+        for target in &targets {
+            dependencies.extend(section(target.get(key)));
+        }
+        dependencies
+    };
+
+    let mut dependencies = section(
+        manifest
+            .get("workspace")
+            .and_then(|w| w.get("dependencies")),
+    );
+    dependencies.extend(with_targets("dependencies"));
+    let mut dev_dependencies = with_targets("dev-dependencies");
+    dev_dependencies.extend(with_targets("build-dependencies"));
+    Some(ParsedManifest {
+        dependencies,
+        dev_dependencies,
+        ..ParsedManifest::default()
+    })
+}
+
+// `"1.0"`, `{ version = "1.0" }`, `{ git = "..." }` or an unresolved `{ workspace = true }`
+fn cargo_requirement(spec: &toml::Value) -> String {
+    if let Some(version) = spec.as_str() {
+        return version.to_string();
+    }
+    let field = |key: &str| spec.get(key).and_then(toml::Value::as_str);
+    if let Some(version) = field("version") {
+        version.to_string()
+    } else if let Some(git) = field("git") {
+        format!("git:{}", git)
+    } else if spec.get("workspace").and_then(toml::Value::as_bool) == Some(true) {
+        "workspace".to_string()
+    } else {
+        ANY_VERSION.to_string()
+    }
+}
+
+fn parse_package_json(content: &str) -> Option<ParsedManifest> {
+    let manifest: serde_json::Value = serde_json::from_str(content).ok()?;
+    let section = |key: &str| -> IndexMap<String, String> {
+        manifest
+            .get(key)
+            .and_then(serde_json::Value::as_object)
+            .into_iter()
+            .flatten()
+            .map(|(name, version)| {
+                let version = version.as_str().unwrap_or(ANY_VERSION);
+                (name.clone(), version.to_string())
+            })
+            .collect()
+    };
+    let mut dependencies = section("dependencies");
+    dependencies.extend(section("optionalDependencies"));
+    Some(ParsedManifest {
+        dependencies,
+        dev_dependencies: section("devDependencies"),
+        peer_dependencies: section("peerDependencies"),
+    })
+}
+
+// One requirement per line; options (`-r`, `-e`, `--index-url`) and comments are skipped
+fn parse_requirements_txt(content: &str) -> Option<ParsedManifest> {
+    let dependencies = content
+        .lines()
+        .map(|line| line.split_once(" #").map_or(line, |(req, _)| req).trim())
+        .filter(|line| !line.is_empty() && !line.starts_with(['#', '-']))
+        .map(|line| {
+            let line = line.split_once(';').map_or(line, |(req, _)| req).trim();
+            let name_end = line
+                .find(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '_' | '.')))
+                .unwrap_or(line.len());
+            let (name, rest) = line.split_at(name_end);
+            // Drop extras like `[security]` before the version specifier
+            let rest = match rest.strip_prefix('[') {
+                Some(extras) => extras.split_once(']').map_or("", |(_, after)| after),
+                None => rest,
+            };
+            let version = rest.trim();
+            let version = if version.is_empty() {
+                ANY_VERSION
+            } else {
+                version
+            };
+            (name.to_string(), version.to_string())
+        })
+        .filter(|(name, _)| !name.is_empty())
+        .collect();
+    Some(ParsedManifest {
+        dependencies,
+        ..ParsedManifest::default()
+    })
+}
+
+// `require path version` lines and `require ( ... )` blocks; `// indirect` entries are kept
+fn parse_go_mod(content: &str) -> Option<ParsedManifest> {
+    let mut dependencies = IndexMap::new();
+    let mut in_block = false;
+    for line in content.lines() {
+        let line = line.split_once("//").map_or(line, |(code, _)| code).trim();
+        let requirement = if in_block {
+            if line == ")" {
+                in_block = false;
+                continue;
+            }
+            line
+        } else if let Some(rest) = line.strip_prefix("require") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+                continue;
+            }
+            rest
+        } else {
+            continue;
+        };
+        let mut parts = requirement.split_whitespace();
+        if let (Some(module), Some(version)) = (parts.next(), parts.next()) {
+            dependencies.insert(module.to_string(), version.to_string());
+        }
+    }
+    Some(ParsedManifest {
+        dependencies,
+        ..ParsedManifest::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(map: &IndexMap<String, String>) -> Vec<&str> {
+        map.keys().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn cargo_skips_path_crates_and_reads_target_tables() {
+        let parsed = parse_cargo_toml(
+            r#"
+[workspace.dependencies]
+serde = "1.0"
+xcontext-core = { path = "core" }
+
+[dependencies]
+serde = { workspace = true }
+xcontext-core = { workspace = true }
+local = { path = "../local" }
+regex = { git = "https://example.com/regex" }
+
+[target.'cfg(windows)'.dependencies]
+winapi = "0.3"
+
+[target.'cfg(unix)'.dev-dependencies]
+nix = { version = "0.29" }
+
+[build-dependencies]
+cc = "1"
+"#,
+        )
+        .unwrap();
+        assert_eq!(names(&parsed.dependencies), ["serde", "regex", "winapi"]);
+        assert_eq!(parsed.dependencies["serde"], "1.0");
+        assert_eq!(
+            parsed.dependencies["regex"],
+            "git:https://example.com/regex"
+        );
+        assert_eq!(names(&parsed.dev_dependencies), ["nix", "cc"]);
+    }
+
+    #[test]
+    fn npm_keeps_peer_dependencies_separate() {
+        let parsed = parse_package_json(
+            r#"{
+                "dependencies": { "react-dom": "^18" },
+                "optionalDependencies": { "fsevents": "^2" },
+                "peerDependencies": { "react": ">=17" },
+                "devDependencies": { "jest": "^29" }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(names(&parsed.dependencies), ["react-dom", "fsevents"]);
+        assert_eq!(names(&parsed.peer_dependencies), ["react"]);
+        assert_eq!(names(&parsed.dev_dependencies), ["jest"]);
+    }
+
+    #[test]
+    fn requirements_txt_skips_options_and_extras() {
+        let parsed = parse_requirements_txt(
+            "# pinned\n-r base.txt\nrequests[security]>=2.0 ; python_version > '3'\nflask\n",
+        )
+        .unwrap();
+        assert_eq!(names(&parsed.dependencies), ["requests", "flask"]);
+        assert_eq!(parsed.dependencies["requests"], ">=2.0");
+        assert_eq!(parsed.dependencies["flask"], ANY_VERSION);
+    }
+
+    #[test]
+    fn go_mod_reads_single_lines_and_blocks() {
+        let parsed = parse_go_mod(
+            "module x\n\nrequire github.com/a/b v1.2.0\n\nrequire (\n\tgolang.org/x/text v0.3.0 // indirect\n)\n",
+        )
+        .unwrap();
+        assert_eq!(
+            names(&parsed.dependencies),
+            ["github.com/a/b", "golang.org/x/text"]
+        );
+    }
+}
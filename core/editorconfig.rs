@@ -0,0 +1,61 @@
+use indexmap::IndexMap;
+#[cfg(feature = "serde_support")]
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+// Properties that describe formatting conventions; editor-specific extensions are dropped
+const SUMMARY_KEYS: &[&str] = &[
+    "indent_style",
+    "indent_size",
+    "tab_width",
+    "end_of_line",
+    "charset",
+    "trim_trailing_whitespace",
+    "insert_final_newline",
+    "max_line_length",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize))]
+pub struct EditorConfigSection {
+    pub glob: String,
+    pub settings: IndexMap<String, String>,
+}
+
+// Reads the project's root '.editorconfig' into one entry per glob section that sets any
+// formatting property, in file order
+pub fn summarize_editorconfig(project_root: &Path) -> Option<Vec<EditorConfigSection>> {
+    let content = fs::read_to_string(project_root.join(".editorconfig")).ok()?;
+    let sections = parse_sections(&content);
+    log::debug!("Parsed {} .editorconfig sections", sections.len());
+    (!sections.is_empty()).then_some(sections)
+}
+
+fn parse_sections(content: &str) -> Vec<EditorConfigSection> {
+    let mut sections: Vec<EditorConfigSection> = Vec::new();
+    let mut current: Option<EditorConfigSection> = None;
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with(['#', ';']) {
+            continue;
+        }
+        if let Some(glob) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.extend(current.take().filter(|s| !s.settings.is_empty()));
+            current = Some(EditorConfigSection {
+                glob: glob.trim().to_string(),
+                settings: IndexMap::new(),
+            });
+            continue;
+        }
+        // Preamble keys (e.g. `root = true`) have no section and are not conventions
+        let (Some(section), Some((key, value))) = (current.as_mut(), line.split_once('=')) else {
+            continue;
+        };
+        let key = key.trim().to_lowercase();
+        if SUMMARY_KEYS.contains(&key.as_str()) {
+            section.settings.insert(key, value.trim().to_lowercase());
+        }
+    }
+    sections.extend(current.filter(|s| !s.settings.is_empty()));
+    sections
+}
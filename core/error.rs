@@ -77,6 +77,12 @@ pub enum AppError {
 
     #[error("Duration Parsing Error: {0}")]
     DurationParse(String),
+
+    #[error("Filter Command Error: {0}")]
+    FilterCommand(String),
+
+    #[error("Git Error: {0}")]
+    Git(String),
 }
 
 #[cfg(feature = "serde_support")]
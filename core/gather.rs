@@ -1,21 +1,30 @@
-use crate::config::Config;
+use crate::config::{Config, ContentTransform, TokenLimitStrategy, TreeConfig};
 use crate::error::{AppError, Result};
-use crate::output_formats::get_builtin_ignore_patterns; // Keep this import
+use crate::output_formats::{BuiltinIgnores, get_builtin_ignore_patterns}; // Keep this import
+use crate::transforms::{apply_transform, redact_env_content};
 use globset::{Glob, GlobSet, GlobSetBuilder};
 use ignore::{WalkBuilder, WalkState};
+use indexmap::IndexMap;
+use indicatif::{ProgressBar, ProgressStyle};
 use log;
 use rayon::prelude::*;
 #[cfg(feature = "serde_support")] // Corrected newline before this line
 use serde::Serialize;
 use std::fs;
+use std::io::{ErrorKind, IsTerminal};
 use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, SystemTime};
 
 #[derive(Debug, Clone)]
 pub struct FileInfo {
     pub path: PathBuf,
     pub content: String,
     pub size: usize,
+    pub modified: Option<SystemTime>,
+    pub mode: Option<u32>, // Unix st_mode; None elsewhere
 }
 
 // Corrected: Made TreeNode public and conditional compilation for Serialize
@@ -30,49 +39,126 @@ pub struct TreeNode {
         serde(skip_serializing_if = "Option::is_none") // Corrected syntax is fine here
     )]
     children: Option<Vec<TreeNode>>,
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    readme: Option<String>,
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(skip_serializing_if = "std::ops::Not::not")
+    )]
+    collapsed: bool,
+}
+
+impl TreeNode {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.node_type == "directory"
+    }
+
+    pub fn children(&self) -> &[TreeNode] {
+        self.children.as_deref().unwrap_or_default()
+    }
+}
+
+#[cfg(unix)]
+fn file_mode(metadata: &fs::Metadata) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    Some(metadata.permissions().mode())
+}
+
+#[cfg(not(unix))]
+fn file_mode(_metadata: &fs::Metadata) -> Option<u32> {
+    None
+}
+
+const PROGRESS_REFRESH: Duration = Duration::from_millis(100);
+
+// Runs a gathering phase on a scoped worker thread while the calling thread redraws a progress
+// bar from the counter the phase bumps. A no-op wrapper when progress is disabled.
+struct Progress {
+    enabled: bool,
+}
+
+impl Progress {
+    fn new(config: &Config, quiet: bool) -> Self {
+        Self {
+            enabled: config.general.show_progress && !quiet && std::io::stderr().is_terminal(),
+        }
+    }
+
+    fn track<R: Send>(
+        &self,
+        message: &'static str,
+        total: Option<usize>,
+        work: impl FnOnce(&AtomicUsize) -> R + Send,
+    ) -> R {
+        let counter = AtomicUsize::new(0);
+        if !self.enabled {
+            return work(&counter);
+        }
+        let bar = match total {
+            Some(total) => ProgressBar::new(total as u64).with_style(
+                ProgressStyle::with_template("{msg} [{bar:30}] {pos}/{len}")
+                    .unwrap_or_else(|_| ProgressStyle::default_bar())
+                    .progress_chars("=> "),
+            ),
+            None => ProgressBar::new_spinner().with_style(
+                ProgressStyle::with_template("{spinner} {msg} {pos} paths")
+                    .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+            ),
+        }
+        .with_message(message);
+
+        let result = thread::scope(|scope| {
+            let handle = scope.spawn(|| work(&counter));
+            while !handle.is_finished() {
+                bar.set_position(counter.load(Ordering::Relaxed) as u64);
+                bar.tick();
+                thread::sleep(PROGRESS_REFRESH);
+            }
+            handle.join()
+        });
+        bar.finish_and_clear();
+        match result {
+            Ok(value) => value,
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    }
+}
+
+const DIRECTORY_README_NAMES: &[&str] = &["README.md", "readme.md", "README.org", "README"];
+
+// Everything one walk of the project produces; commands that need several views of the files
+// (context, metrics, watch targets) derive them from this instead of walking again
+#[derive(Debug, Clone, Default)]
+pub struct GatherResult {
+    pub source_files: Vec<FileInfo>,
+    pub docs_files: Vec<FileInfo>,
+    pub tree_path_types: Vec<(String, bool)>, // Relative path and whether it is a directory
+}
+
+impl GatherResult {
+    pub fn all_files(&self) -> impl Iterator<Item = &FileInfo> {
+        self.source_files.iter().chain(self.docs_files.iter())
+    }
 }
 
 pub fn gather_files_and_tree(
     project_root: &Path,
     config: &Config,
     quiet: bool, // Keep quiet for conditional logging
-) -> Result<(Vec<FileInfo>, Vec<FileInfo>, Vec<(String, bool)>)> {
+) -> Result<GatherResult> {
     log::debug!("Starting file and tree gathering process...");
-    let tree_include_patterns = config.get_effective_include(&config.tree.include);
-    let tree_exclude_patterns = config.get_effective_exclude(&config.tree.exclude);
-    let source_include_patterns = config.get_effective_include(&config.source.include);
-    let source_exclude_patterns = config.get_effective_exclude(&config.source.exclude);
-    let docs_include_patterns = config.get_effective_include(&config.docs.include);
-    let docs_exclude_patterns = config.get_effective_exclude(&config.docs.exclude);
-
     log::trace!("Building glob sets for filtering...");
-    let tree_include_set = build_glob_set_from_vec(tree_include_patterns)?;
-    let tree_exclude_set = build_glob_set_from_vec(tree_exclude_patterns)?;
-    let has_tree_includes = !tree_include_patterns.is_empty();
-
-    let source_include_set = build_glob_set_from_vec(source_include_patterns)?;
-    let source_exclude_set = build_glob_set_from_vec(source_exclude_patterns)?;
-    let has_source_includes = !source_include_patterns.is_empty();
-
-    let docs_active = config.is_docs_section_active();
-    let docs_include_set = if docs_active {
-        build_glob_set_from_vec(docs_include_patterns)?
-    } else {
-        GlobSet::empty()
-    };
-    let docs_exclude_set = if docs_active {
-        build_glob_set_from_vec(docs_exclude_patterns)?
-    } else {
-        GlobSet::empty()
-    };
-    let has_docs_includes = docs_active && !docs_include_patterns.is_empty();
-
-    let builtin_ignores = get_builtin_ignore_patterns();
-    let common_builtin_exclude_set = build_glob_set_from_vec(&builtin_ignores.common)?;
-    let tree_builtin_exclude_set = build_glob_set_from_vec(&builtin_ignores.tree)?;
-    let source_builtin_exclude_set = build_glob_set_from_vec(&builtin_ignores.source)?;
-    let docs_builtin_exclude_set = build_glob_set_from_vec(&builtin_ignores.docs)?;
-    let use_builtin_ignores = config.get_effective_builtin_ignore();
+    let filters = FilterSets::from_config(config)?;
+    let progress = Progress::new(config, quiet);
+    let docs_active = filters.docs_active;
+    let use_builtin_ignores = filters.use_builtin;
     log::trace!("Glob sets built successfully.");
 
     let mut builder = WalkBuilder::new(project_root);
@@ -84,6 +170,32 @@ pub fn gather_files_and_tree(
     builder.git_ignore(use_global_gitignore);
     builder.git_exclude(use_global_gitignore);
     builder.require_git(false);
+
+    let dockerignore_path = project_root.join(".dockerignore");
+    if config.general.respect_dockerignore && dockerignore_path.is_file() {
+        // Docker anchors patterns at the build context root, so only the root file applies
+        if let Some(err) = builder.add_ignore(&dockerignore_path) {
+            log::warn!("Failed to parse {}: {}", dockerignore_path.display(), err);
+        } else {
+            log::debug!(
+                "Applying ignore patterns from {}",
+                dockerignore_path.display()
+            );
+        }
+    }
+    for ignore_file in &config.general.cli_ignore_files {
+        if !ignore_file.is_file() {
+            return Err(AppError::InvalidArgument(format!(
+                "Ignore file not found: {}",
+                ignore_file.display()
+            )));
+        }
+        if let Some(err) = builder.add_ignore(ignore_file) {
+            log::warn!("Failed to parse {}: {}", ignore_file.display(), err);
+        } else {
+            log::debug!("Applying ignore patterns from {}", ignore_file.display());
+        }
+    }
     log::debug!(
         "WalkBuilder configured (gitignore: {}, builtin: {})",
         use_global_gitignore,
@@ -92,61 +204,97 @@ pub fn gather_files_and_tree(
 
     let walker = builder.build_parallel();
     let project_root_clone = project_root.to_path_buf();
+    // Gitignored directories never reach the walker callback, so they cannot be collapsed
+    let collapse_set = build_glob_set_from_vec(&config.tree.collapse)?;
+    let output_dirs = output_paths_to_skip(project_root, config);
+    let pruner = filters.pruner.clone();
 
     #[derive(Debug)]
     struct WalkedPathInfo {
         path: PathBuf,
         relative_path: PathBuf,
         is_dir: bool,
+        collapsed: bool,
     }
     let (tx_walked, rx_walked) = mpsc::channel::<WalkedPathInfo>();
     let tx_for_closure = tx_walked.clone();
 
     log::info!("Walking project directory: {}", project_root.display());
-    walker.run(move || {
-        let tx_thread = tx_for_closure.clone();
-        let proj_root = project_root_clone.clone();
-
-        Box::new(move |entry_result| {
-            match entry_result {
-                Ok(entry) => {
-                    let path = entry.path();
-                    if entry.depth() == 0 {
-                        return WalkState::Continue;
-                    }
-                    // Skip cache directory explicitly if walkbuilder doesn't handle it
-                    if path.strip_prefix(&proj_root).map_or(false, |rel| {
-                        rel.starts_with(crate::config::DEFAULT_CACHE_DIR) // Use constant
-                    }) {
-                        log::trace!("Skipping cache directory: {}", path.display());
-                        return WalkState::Skip;
-                    }
+    progress.track("Walking", None, |walked_count| {
+        walker.run(|| {
+            let tx_thread = tx_for_closure.clone();
+            let proj_root = project_root_clone.clone();
+            let collapse_set = collapse_set.clone();
+            let output_dirs = output_dirs.clone();
+            let pruner = pruner.clone();
 
-                    if let Some(relative_path) = pathdiff::diff_paths(path, &proj_root) {
-                        let is_dir = entry.file_type().map_or(false, |ft| ft.is_dir());
-                        log::trace!("Walked path: {}", relative_path.display());
-                        if tx_thread
-                            .send(WalkedPathInfo {
-                                path: path.to_path_buf(),
-                                relative_path,
-                                is_dir,
-                            })
-                            .is_err()
+            Box::new(move |entry_result| {
+                match entry_result {
+                    Ok(entry) => {
+                        let path = entry.path();
+                        if entry.depth() == 0 {
+                            return WalkState::Continue;
+                        }
+                        // Skip cache directory explicitly if walkbuilder doesn't handle it
+                        if path.strip_prefix(&proj_root).map_or(false, |rel| {
+                            rel.starts_with(crate::config::DEFAULT_CACHE_DIR) // Use constant
+                        }) {
+                            log::trace!("Skipping cache directory: {}", path.display());
+                            return WalkState::Skip;
+                        }
+                        // Never ingest previously generated output
+                        if path
+                            .strip_prefix(&proj_root)
+                            .is_ok_and(|rel| output_dirs.iter().any(|out| rel.starts_with(out)))
                         {
-                            log::error!("Receiver dropped for walked paths, stopping walk early.");
-                            return WalkState::Quit;
+                            log::trace!("Skipping output path: {}", path.display());
+                            return WalkState::Skip;
+                        }
+
+                        if let Some(relative_path) = pathdiff::diff_paths(path, &proj_root) {
+                            let is_dir = entry.file_type().map_or(false, |ft| ft.is_dir());
+                            let collapsed = is_dir
+                                && (collapse_set.is_match(&relative_path)
+                                    || collapse_set
+                                        .is_match(relative_path.join("dummy_file_for_dir_match")));
+                            // Excluded everywhere, so don't descend (or report the dir to the tree)
+                            if is_dir && !collapsed && pruner.can_prune(&relative_path) {
+                                log::trace!("Pruning excluded directory: {}", path.display());
+                                return WalkState::Skip;
+                            }
+                            log::trace!("Walked path: {}", relative_path.display());
+                            walked_count.fetch_add(1, Ordering::Relaxed);
+                            if tx_thread
+                                .send(WalkedPathInfo {
+                                    path: path.to_path_buf(),
+                                    relative_path,
+                                    is_dir,
+                                    collapsed,
+                                })
+                                .is_err()
+                            {
+                                log::error!(
+                                    "Receiver dropped for walked paths, stopping walk early."
+                                );
+                                return WalkState::Quit;
+                            }
+                            if collapsed {
+                                log::trace!("Collapsing directory: {}", path.display());
+                                return WalkState::Skip;
+                            }
+                        } else {
+                            log::warn!("Could not get relative path for: {}", path.display());
                         }
-                    } else {
-                        log::warn!("Could not get relative path for: {}", path.display());
+                    }
+                    Err(e) => {
+                        log::warn!("Error walking directory: {}", e);
                     }
                 }
-                Err(e) => {
-                    log::warn!("Error walking directory: {}", e);
-                }
-            }
-            WalkState::Continue
+                WalkState::Continue
+            })
         })
     });
+    drop(tx_for_closure);
     drop(tx_walked);
 
     let walked_paths: Vec<WalkedPathInfo> = rx_walked.into_iter().collect();
@@ -177,54 +325,34 @@ pub fn gather_files_and_tree(
             continue;
         }
 
-        let tree_git_ignore = config.get_effective_gitignore(&config.tree.use_gitignore);
-        let docs_git_ignore = config.get_effective_gitignore(&config.docs.use_gitignore);
-        let source_git_ignore = config.get_effective_gitignore(&config.source.use_gitignore);
+        if walked_info.collapsed {
+            if config.tree.enabled {
+                log::trace!(
+                    "Including collapsed dir in tree: {}",
+                    relative_path.display()
+                );
+                tree_candidates.push((relative_path.to_string_lossy().into_owned(), true));
+            }
+            continue;
+        }
 
         let include_in_tree = config.tree.enabled
-            && should_include(
-                relative_path,
-                is_dir,
-                &tree_include_set,
-                has_tree_includes,
-                &tree_exclude_set,
-                tree_git_ignore,
-                project_root, // Pass project root if needed by gitignore logic internally
-                use_builtin_ignores,
-                &common_builtin_exclude_set,
-                &tree_builtin_exclude_set,
-            );
+            && filters
+                .decision(relative_path, is_dir, &filters.tree)
+                .is_included();
 
         let include_in_docs = !is_dir
             && docs_active
-            && should_include(
-                relative_path,
-                false, // is_dir is false for files
-                &docs_include_set,
-                has_docs_includes,
-                &docs_exclude_set,
-                docs_git_ignore,
-                project_root,
-                use_builtin_ignores,
-                &common_builtin_exclude_set,
-                &docs_builtin_exclude_set,
-            );
+            && filters
+                .decision(relative_path, false, &filters.docs)
+                .is_included();
 
         let include_in_source = !is_dir
             && !include_in_docs // Don't include if it's already a doc file
             && config.source.enabled
-            && should_include(
-                relative_path,
-                false, // is_dir is false for files
-                &source_include_set,
-                has_source_includes,
-                &source_exclude_set,
-                source_git_ignore,
-                project_root,
-                use_builtin_ignores,
-                &common_builtin_exclude_set,
-                &source_builtin_exclude_set,
-            );
+            && filters
+                .decision(relative_path, false, &filters.source)
+                .is_included();
 
         if include_in_tree {
             log::trace!("Including in tree: {}", relative_path.display());
@@ -244,63 +372,182 @@ pub fn gather_files_and_tree(
     }
     log::debug!("Path filtering complete.");
 
+    if config.general.exclude_untracked {
+        let untracked = crate::git::list_untracked_files(project_root)?;
+        let before = source_file_paths.len() + docs_file_paths.len();
+        source_file_paths.retain(|path| !untracked.contains(path));
+        docs_file_paths.retain(|path| !untracked.contains(path));
+        log::info!(
+            "Excluded {} untracked files.",
+            before - source_file_paths.len() - docs_file_paths.len()
+        );
+    }
+
     log::info!(
         "Reading content for {} source files and {} docs files...",
         source_file_paths.len(),
         docs_file_paths.len()
     );
 
-    let read_files = |paths: Vec<PathBuf>| -> (Vec<FileInfo>, Vec<AppError>) {
-        let results: Vec<_> = paths
-            .into_par_iter()
-            .map(|path| match fs::read(&path) {
-                Ok(bytes) => {
-                    let size = bytes.len();
-                    match String::from_utf8(bytes) {
-                        Ok(content) => Ok(FileInfo {
-                            path,
-                            content,
-                            size,
-                        }),
-                        Err(e) => {
-                            log::debug!("Skipping non-UTF-8 file: {} ({})", path.display(), e);
-                            Err(AppError::DataLoading(format!(
-                                "Skipped non-UTF-8 file: {}",
-                                path.display()
-                            )))
+    let read_retries = config.general.read_retries;
+    let read_files =
+        |paths: Vec<PathBuf>, read_count: &AtomicUsize| -> (Vec<FileInfo>, Vec<AppError>, usize) {
+            let results: Vec<_> = paths
+                .into_par_iter()
+                .map(|path| match read_with_retries(&path, read_retries) {
+                    Ok(bytes) => {
+                        let size = bytes.len();
+                        let metadata = fs::metadata(&path).ok();
+                        let modified = metadata.as_ref().and_then(|m| m.modified().ok());
+                        let mode = metadata.as_ref().and_then(file_mode);
+                        match String::from_utf8(bytes) {
+                            Ok(content) => Ok(FileInfo {
+                                path,
+                                content,
+                                size,
+                                modified,
+                                mode,
+                            }),
+                            Err(e) => {
+                                log::debug!("Skipping non-UTF-8 file: {} ({})", path.display(), e);
+                                Err(AppError::DataLoading(format!(
+                                    "Skipped non-UTF-8 file: {}",
+                                    path.display()
+                                )))
+                            }
                         }
                     }
-                }
-                Err(e) => Err(AppError::FileRead {
-                    path: path.clone(),
-                    source: e,
-                }),
-            })
-            .collect();
+                    Err(e) => Err(AppError::FileRead {
+                        path: path.clone(),
+                        source: e,
+                    }),
+                })
+                .inspect(|_| {
+                    read_count.fetch_add(1, Ordering::Relaxed);
+                })
+                .collect();
 
-        let mut files = Vec::new();
-        let mut errors = Vec::new();
-        for res in results {
-            match res {
-                Ok(info) => files.push(info),
-                Err(AppError::DataLoading(_)) => { /* Already logged, skip */ }
-                Err(e) => errors.push(e),
+            let mut files = Vec::new();
+            let mut errors = Vec::new();
+            let mut non_utf8 = 0;
+            for res in results {
+                match res {
+                    Ok(info) => files.push(info),
+                    Err(AppError::DataLoading(_)) => non_utf8 += 1, // Already logged per file
+                    Err(e) => errors.push(e),
+                }
             }
-        }
-        (files, errors)
-    };
+            (files, errors, non_utf8)
+        };
 
-    let (mut final_source_files, source_errors) = read_files(source_file_paths);
-    let (mut final_docs_files, docs_errors) = read_files(docs_file_paths);
+    let total_to_read = source_file_paths.len() + docs_file_paths.len();
+    let (
+        (mut final_source_files, source_errors, source_non_utf8),
+        (mut final_docs_files, docs_errors, docs_non_utf8),
+    ) = progress.track("Reading", Some(total_to_read), |read_count| {
+        (
+            read_files(source_file_paths, read_count),
+            read_files(docs_file_paths, read_count),
+        )
+    });
     file_read_errors.extend(source_errors);
     file_read_errors.extend(docs_errors);
     log::info!("File reading complete.");
+    if source_non_utf8 + docs_non_utf8 > 0 {
+        log::warn!(
+            "Skipped {} non-UTF-8 files (run with -vv to list them).",
+            source_non_utf8 + docs_non_utf8
+        );
+    }
+
+    if config.common_filters.exclude_generated {
+        let max_line_length = config.common_filters.max_line_length;
+        let dropped = drop_generated_files(&mut final_source_files, max_line_length)
+            + drop_generated_files(&mut final_docs_files, max_line_length);
+        log::info!("Excluded {} likely-generated files.", dropped);
+    }
+
+    if let Some(max_age) = config.get_source_max_age()? {
+        let dropped = drop_stale_files(&mut final_source_files, max_age);
+        log::info!(
+            "Excluded {} source files not modified within {}.",
+            dropped,
+            config.source.max_age.as_deref().unwrap_or_default()
+        );
+    }
+
+    if let Some(max_lines) = config.source.max_lines {
+        let dropped = drop_files_over_line_limit(&mut final_source_files, max_lines);
+        log::info!(
+            "Excluded {} source files with more than {} lines.",
+            dropped,
+            max_lines
+        );
+    }
+
+    if config.source.redact_env_values {
+        // Docs are covered too so an env file never leaks through a broader docs pattern
+        for file in final_source_files
+            .iter_mut()
+            .chain(final_docs_files.iter_mut())
+            .filter(|f| is_env_file(&f.path))
+        {
+            log::debug!("Redacting values in env file: {}", file.path.display());
+            file.content = redact_env_content(&file.content);
+        }
+    }
+
+    if !config.source.transforms.is_empty() {
+        for file in final_source_files.iter_mut() {
+            if let Some(transform) = transform_for(&file.path, &config.source.transforms) {
+                log::trace!("Applying {:?} to {}", transform, file.path.display());
+                file.content = apply_transform(transform, &file.path, &file.content);
+            }
+        }
+    }
+
+    if let Some(max_chars) = config.source.truncate_lines_over {
+        let affected = truncate_long_lines(&mut final_source_files, max_chars);
+        if affected > 0 {
+            log::info!(
+                "Truncated long lines in {} source files to {} chars.",
+                affected,
+                max_chars
+            );
+        }
+    }
+
+    if let Some(max_tokens) = config.source.max_tokens_per_file {
+        let strategy = config.source.max_tokens_strategy;
+        let affected = enforce_file_token_limit(&mut final_source_files, max_tokens, strategy)?;
+        log::info!(
+            "Applied {:?} to {} source files over {} tokens.",
+            strategy,
+            affected,
+            max_tokens
+        );
+    }
+
+    if let Some(max_bytes) = config.source.max_content_bytes {
+        let truncated = truncate_file_contents(&mut final_source_files, max_bytes);
+        log::info!(
+            "Truncated {} source files to {} bytes.",
+            truncated,
+            max_bytes
+        );
+    }
 
     // Sort results for deterministic output
     final_source_files.par_sort_unstable_by(|a, b| a.path.cmp(&b.path));
     final_docs_files.par_sort_unstable_by(|a, b| a.path.cmp(&b.path));
     tree_candidates.par_sort_unstable_by(|a, b| a.0.cmp(&b.0));
 
+    if !file_read_errors.is_empty() {
+        log::warn!(
+            "{} files could not be read or were skipped.",
+            file_read_errors.len()
+        );
+    }
     // Report errors gathered during file reading if not quiet
     if !file_read_errors.is_empty() && !quiet {
         use colored::Colorize; // Only needed here
@@ -315,10 +562,321 @@ pub fn gather_files_and_tree(
         eprintln!("---");
     }
 
-    Ok((final_source_files, final_docs_files, tree_candidates))
+    if config.general.fail_on_empty && final_source_files.is_empty() && final_docs_files.is_empty()
+    {
+        return Err(AppError::InvalidArgument(format!(
+            "No source or docs files were gathered from {} (general.fail_on_empty is set). Check the project root and include/exclude filters.",
+            project_root.display()
+        )));
+    }
+
+    Ok(GatherResult {
+        source_files: final_source_files,
+        docs_files: final_docs_files,
+        tree_path_types: tree_candidates,
+    })
+}
+
+// Save locations inside the project, relative to its root. The root itself is never returned.
+fn output_paths_to_skip(project_root: &Path, config: &Config) -> Vec<PathBuf> {
+    [
+        Some(&config.save.output_dir),
+        config.save.cli_save_path.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(|path| {
+        let relative = if path.is_absolute() {
+            let canonical_root = project_root.canonicalize().ok();
+            path.strip_prefix(project_root)
+                .ok()
+                .or_else(|| path.strip_prefix(canonical_root.as_ref()?).ok())?
+                .to_path_buf()
+        } else {
+            path.clone()
+        };
+        let normalized: PathBuf = relative
+            .components()
+            .filter(|c| !matches!(c, Component::CurDir))
+            .collect();
+        (!normalized.as_os_str().is_empty()).then_some(normalized)
+    })
+    .collect()
+}
+
+const READ_RETRY_BASE_DELAY: Duration = Duration::from_millis(50);
+
+// Retries reads that fail with transient error kinds, backing off exponentially
+fn read_with_retries(path: &Path, retries: usize) -> std::io::Result<Vec<u8>> {
+    let mut attempt = 0;
+    loop {
+        match fs::read(path) {
+            Ok(bytes) => return Ok(bytes),
+            Err(e) if attempt < retries && is_transient_read_error(&e) => {
+                attempt += 1;
+                let delay = READ_RETRY_BASE_DELAY * 2u32.saturating_pow(attempt as u32 - 1);
+                log::warn!(
+                    "Transient error reading '{}' ({}), retry {}/{} in {:?}",
+                    path.display(),
+                    e,
+                    attempt,
+                    retries,
+                    delay
+                );
+                thread::sleep(delay);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn is_transient_read_error(err: &std::io::Error) -> bool {
+    matches!(
+        err.kind(),
+        ErrorKind::Interrupted | ErrorKind::TimedOut | ErrorKind::WouldBlock
+    )
 }
 
-fn build_glob_set_from_vec(patterns: &[String]) -> Result<GlobSet> {
+// Removes files whose mtime is older than `max_age`, returning how many were dropped.
+// Files without a readable mtime are kept.
+fn drop_stale_files(files: &mut Vec<FileInfo>, max_age: Duration) -> usize {
+    let Some(cutoff) = SystemTime::now().checked_sub(max_age) else {
+        return 0;
+    };
+    let before = files.len();
+    files.retain(|file| match file.modified {
+        Some(modified) if modified < cutoff => {
+            log::info!("Excluding stale file: {}", file.path.display());
+            false
+        }
+        _ => true,
+    });
+    before - files.len()
+}
+
+// Removes files with more than `max_lines` lines, returning how many were dropped
+fn drop_files_over_line_limit(files: &mut Vec<FileInfo>, max_lines: usize) -> usize {
+    let before = files.len();
+    files.retain(|file| {
+        let line_count = file.content.lines().count();
+        if line_count <= max_lines {
+            return true;
+        }
+        log::info!(
+            "Excluding {} ({} lines exceeds source.max_lines = {}).",
+            file.path.display(),
+            line_count,
+            max_lines
+        );
+        false
+    });
+    before - files.len()
+}
+
+fn is_env_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|name| name == ".env" || name.starts_with(".env."))
+}
+
+// Picks the `[source.transforms]` entry for a file by extension; env files use the "env" key
+fn transform_for(
+    path: &Path,
+    transforms: &IndexMap<String, ContentTransform>,
+) -> Option<ContentTransform> {
+    let key = if is_env_file(path) {
+        "env".to_string()
+    } else {
+        path.extension()?.to_str()?.to_lowercase()
+    };
+    transforms
+        .iter()
+        .find(|(ext, _)| ext.trim_start_matches('.').eq_ignore_ascii_case(&key))
+        .map(|(_, transform)| *transform)
+}
+
+// Cuts content longer than `max_bytes` at the nearest preceding UTF-8 char boundary and appends
+// a marker, returning how many files were truncated
+fn truncate_file_contents(files: &mut [FileInfo], max_bytes: usize) -> usize {
+    let mut truncated = 0;
+    for file in files.iter_mut().filter(|f| f.content.len() > max_bytes) {
+        let total = file.content.len();
+        let mut cut = max_bytes;
+        while !file.content.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        file.content.truncate(cut);
+        file.content.push_str(&format!(
+            "\n... [truncated: showing {} of {} bytes]",
+            cut, total
+        ));
+        log::info!(
+            "Truncated {} to {} of {} bytes (source.max_content_bytes).",
+            file.path.display(),
+            cut,
+            total
+        );
+        truncated += 1;
+    }
+    truncated
+}
+
+// Drops or truncates files whose estimated token count exceeds `max_tokens`, returning how many
+// were affected. Falls back to the bytes/4 estimate when cl100k_base is unavailable.
+fn enforce_file_token_limit(
+    files: &mut Vec<FileInfo>,
+    max_tokens: usize,
+    strategy: TokenLimitStrategy,
+) -> Result<usize> {
+    let precise = crate::tokens::tokenizer_available();
+    let estimate = |text: &str| -> Result<usize> {
+        if precise {
+            crate::tokens::estimate_tokens(text)
+        } else {
+            Ok(crate::tokens::approximate_tokens(text))
+        }
+    };
+    let counts = files
+        .par_iter()
+        .map(|file| estimate(&file.content))
+        .collect::<Result<Vec<usize>>>()?;
+
+    let mut affected = 0;
+    let mut kept = Vec::with_capacity(files.len());
+    for (mut file, tokens) in files.drain(..).zip(counts) {
+        if tokens <= max_tokens {
+            kept.push(file);
+            continue;
+        }
+        affected += 1;
+        match strategy {
+            TokenLimitStrategy::Exclude => log::info!(
+                "Excluding {} (~{} tokens exceeds source.max_tokens_per_file = {}).",
+                file.path.display(),
+                tokens,
+                max_tokens
+            ),
+            TokenLimitStrategy::Truncate => {
+                let cut = if precise {
+                    crate::tokens::token_prefix_len(&file.content, max_tokens)?
+                } else {
+                    crate::tokens::approximate_prefix_len(&file.content, max_tokens)
+                };
+                file.content.truncate(cut);
+                file.content.push_str(&format!(
+                    "\n... [truncated: showing {} of ~{} tokens]",
+                    max_tokens, tokens
+                ));
+                log::info!(
+                    "Truncated {} to {} of ~{} tokens (source.max_tokens_per_file).",
+                    file.path.display(),
+                    max_tokens,
+                    tokens
+                );
+                kept.push(file);
+            }
+        }
+    }
+    *files = kept;
+    Ok(affected)
+}
+
+const LINE_TRUNCATION_MARKER: &str = "…[truncated]";
+
+// Cuts every line longer than `max_chars` characters and appends a marker, keeping the line count
+// intact; returns how many files had at least one line cut
+fn truncate_long_lines(files: &mut [FileInfo], max_chars: usize) -> usize {
+    let mut affected = 0;
+    for file in files.iter_mut() {
+        if !file.content.lines().any(|line| line.len() > max_chars) {
+            continue; // Byte length bounds char count, so this skips the common case cheaply
+        }
+        let mut cut_lines = 0;
+        let mut content = String::with_capacity(file.content.len());
+        for line in file.content.split_inclusive('\n') {
+            let (text, ending) = match line.strip_suffix("\r\n") {
+                Some(text) => (text, "\r\n"),
+                None => line
+                    .strip_suffix('\n')
+                    .map_or((line, ""), |text| (text, "\n")),
+            };
+            match text.char_indices().nth(max_chars) {
+                Some((cut, _)) => {
+                    content.push_str(&text[..cut]);
+                    content.push_str(LINE_TRUNCATION_MARKER);
+                    cut_lines += 1;
+                }
+                None => content.push_str(text),
+            }
+            content.push_str(ending);
+        }
+        if cut_lines == 0 {
+            continue;
+        }
+        file.content = content;
+        log::info!(
+            "Truncated {} lines in {} to {} chars (source.truncate_lines_over).",
+            cut_lines,
+            file.path.display(),
+            max_chars
+        );
+        affected += 1;
+    }
+    affected
+}
+
+const GENERATED_HEADER_MARKERS: &[&str] = &["Code generated", "@generated", "DO NOT EDIT"];
+const GENERATED_HEADER_LINES: usize = 5;
+
+// Removes files that look generated or minified, returning how many were dropped
+fn drop_generated_files(files: &mut Vec<FileInfo>, max_line_length: usize) -> usize {
+    let before = files.len();
+    files.retain(|file| match generated_file_reason(file, max_line_length) {
+        Some(reason) => {
+            log::info!(
+                "Excluding likely-generated file: {} ({})",
+                file.path.display(),
+                reason
+            );
+            false
+        }
+        None => true,
+    });
+    before - files.len()
+}
+
+fn generated_file_reason(file: &FileInfo, max_line_length: usize) -> Option<String> {
+    let filename = file
+        .path
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    if filename.contains(".min.") {
+        return Some("minified filename".to_string());
+    }
+    if file.path.extension().is_some_and(|ext| ext == "map") {
+        return Some("source map".to_string());
+    }
+    if file
+        .content
+        .lines()
+        .take(GENERATED_HEADER_LINES)
+        .any(|line| GENERATED_HEADER_MARKERS.iter().any(|m| line.contains(m)))
+    {
+        return Some("generated-code header".to_string());
+    }
+    if max_line_length > 0
+        && file
+            .content
+            .lines()
+            .any(|line| line.len() > max_line_length && line.chars().count() > max_line_length)
+    {
+        return Some(format!("line longer than {} characters", max_line_length));
+    }
+    None
+}
+
+pub(crate) fn build_glob_set_from_vec(patterns: &[String]) -> Result<GlobSet> {
     let mut builder = GlobSetBuilder::new();
     for pattern_str in patterns {
         let mut processed_pattern = pattern_str.trim().to_string();
@@ -337,10 +895,13 @@ fn build_glob_set_from_vec(patterns: &[String]) -> Result<GlobSet> {
             Err(e) => {
                 // Corrected: Use double quotes for format string
                 log::error!("Invalid glob pattern \"{}\": {}", pattern_str, e);
+                let hint = glob_error_hint(pattern_str)
+                    .map(|h| format!(" Hint: {}", h))
+                    .unwrap_or_default();
                 return Err(AppError::Glob(format!(
                     // Corrected: Use double quotes for format string
-                    "Invalid glob pattern \"{}\" (processed as \"{}\"): {}",
-                    pattern_str, processed_pattern, e
+                    "Invalid glob pattern \"{}\" (processed as \"{}\"): {}.{}",
+                    pattern_str, processed_pattern, e, hint
                 )));
             }
         }
@@ -351,75 +912,425 @@ fn build_glob_set_from_vec(patterns: &[String]) -> Result<GlobSet> {
     })
 }
 
-fn should_include(
-    relative_path: &Path,
-    is_dir: bool,
-    include_set: &GlobSet,
+// Looks for common mistakes behind an opaque globset compile error
+fn glob_error_hint(pattern: &str) -> Option<&'static str> {
+    let mut depth = 0i32;
+    for c in pattern.chars() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ => {}
+        }
+        if depth < 0 {
+            return Some("found '}' without a matching '{'.");
+        }
+    }
+    if depth > 0 {
+        return Some("found '{' without a matching '}', e.g. use '*.{rs,toml}'.");
+    }
+    if pattern.contains('\\') {
+        return Some("use '/' as the path separator in patterns, even on Windows.");
+    }
+    if pattern.matches('[').count() != pattern.matches(']').count() {
+        return Some("character classes need a closing ']', e.g. '[ab].txt'.");
+    }
+    None
+}
+
+// A compiled glob set that remembers its source patterns, so matches can be reported by name
+struct PatternSet {
+    set: GlobSet,
+    patterns: Vec<String>,
+}
+
+impl PatternSet {
+    fn build(patterns: &[String]) -> Result<Self> {
+        Ok(Self {
+            set: build_glob_set_from_vec(patterns)?,
+            patterns: patterns.to_vec(),
+        })
+    }
+
+    fn empty() -> Self {
+        Self {
+            set: GlobSet::empty(),
+            patterns: Vec::new(),
+        }
+    }
+
+    // Directories also match patterns targeting their contents (e.g. 'target/' -> 'target/**')
+    fn matching_pattern(&self, relative_path: &Path, is_dir: bool) -> Option<&str> {
+        let dir_probe = is_dir.then(|| relative_path.join("dummy_file_for_dir_match"));
+        let matched_path = if self.set.is_match(relative_path) {
+            relative_path
+        } else {
+            dir_probe
+                .as_deref()
+                .filter(|probe| self.set.is_match(probe))?
+        };
+        self.set
+            .matches(matched_path)
+            .first()
+            .and_then(|&index| self.patterns.get(index))
+            .map(String::as_str)
+    }
+}
+
+struct SectionFilter {
+    include: PatternSet,
     has_includes: bool, // True if include patterns were provided
-    exclude_set: &GlobSet,
-    _use_gitignore: bool, // Handled by WalkBuilder, keep param for signature consistency?
-    _project_root: &Path, // Potentially needed if gitignore logic were here
-    use_builtin: bool,
-    common_builtin_exclude: &GlobSet,
-    section_builtin_exclude: &GlobSet,
-) -> bool {
-    // 1. Check Explicit Excludes
-    if exclude_set.is_match(relative_path)
-        || (is_dir && exclude_set.is_match(relative_path.join("dummy_file_for_dir_match")))
-    {
-        log::trace!(
-            "Path excluded by explicit exclude set: {}",
-            relative_path.display()
-        );
-        return false;
+    exclude: PatternSet,
+    builtin_exclude: PatternSet,
+}
+
+impl SectionFilter {
+    fn build(include: &[String], exclude: &[String], builtin_exclude: &[String]) -> Result<Self> {
+        Ok(Self {
+            include: PatternSet::build(include)?,
+            has_includes: !include.is_empty(),
+            exclude: PatternSet::build(exclude)?,
+            builtin_exclude: PatternSet::build(builtin_exclude)?,
+        })
     }
 
-    // 2. Check Explicit Includes (if any were provided)
-    // Check both file and potential directory match for includes
-    let included_explicitly = !has_includes
-        || include_set.is_match(relative_path)
-        || (is_dir && include_set.is_match(relative_path.join("dummy_file_for_dir_match")));
+    fn disabled() -> Self {
+        Self {
+            include: PatternSet::empty(),
+            has_includes: false,
+            exclude: PatternSet::empty(),
+            builtin_exclude: PatternSet::empty(),
+        }
+    }
+}
 
-    if !included_explicitly {
-        log::trace!(
-            "Path not included by explicit include set: {}",
-            relative_path.display()
-        );
-        return false;
+// Directories whose whole subtree is excluded from every active section; the walker skips them
+#[derive(Clone)]
+struct DirPruner {
+    sections: Vec<GlobSet>, // One set of subtree-excluding patterns per active section
+}
+
+impl DirPruner {
+    fn build(config: &Config, builtin_ignores: &BuiltinIgnores, docs_active: bool) -> Result<Self> {
+        let use_builtin = config.get_effective_builtin_ignore();
+        let section_sets = [
+            (
+                config.tree.enabled,
+                config.get_effective_exclude(&config.tree.exclude),
+                &builtin_ignores.tree,
+            ),
+            (
+                config.source.enabled,
+                config.get_effective_exclude(&config.source.exclude),
+                &builtin_ignores.source,
+            ),
+            (
+                docs_active,
+                config.get_effective_exclude(&config.docs.exclude),
+                &builtin_ignores.docs,
+            ),
+        ];
+        let mut sections = Vec::new();
+        for (active, exclude, builtin) in section_sets {
+            if !active {
+                continue;
+            }
+            let mut patterns = subtree_patterns(exclude);
+            if use_builtin {
+                patterns.extend(subtree_patterns(builtin));
+                patterns.extend(subtree_patterns(&builtin_ignores.common));
+            }
+            sections.push(build_glob_set_from_vec(&patterns)?);
+        }
+        Ok(Self { sections })
     }
 
-    // 3. Gitignore filtering is handled by the WalkBuilder itself
+    // Excludes are checked before includes, so a subtree excluded everywhere can never be gathered
+    fn can_prune(&self, relative_path: &Path) -> bool {
+        let probe = relative_path.join("dummy_file_for_dir_match");
+        !self.sections.is_empty() && self.sections.iter().all(|set| set.is_match(&probe))
+    }
+}
 
-    // 4. Check Built-in Ignores
-    if use_builtin {
-        if common_builtin_exclude.is_match(relative_path)
-            || (is_dir
-                && common_builtin_exclude.is_match(relative_path.join("dummy_file_for_dir_match")))
-        {
+// Only patterns covering everything below a directory ('dir/' or 'dir/**') are safe to prune on
+fn subtree_patterns(patterns: &[String]) -> Vec<String> {
+    patterns
+        .iter()
+        .filter(|p| {
+            let p = p.trim();
+            (p.ends_with('/') && p.len() > 1) || p.ends_with("/**")
+        })
+        .cloned()
+        .collect()
+}
+
+struct FilterSets {
+    tree: SectionFilter,
+    source: SectionFilter,
+    docs: SectionFilter,
+    common_builtin_exclude: PatternSet,
+    use_builtin: bool,
+    docs_active: bool,
+    pruner: DirPruner,
+}
+
+impl FilterSets {
+    fn from_config(config: &Config) -> Result<Self> {
+        let builtin_ignores = get_builtin_ignore_patterns();
+        let docs_active = config.is_docs_section_active();
+        Ok(Self {
+            tree: SectionFilter::build(
+                config.get_effective_include(&config.tree.include),
+                config.get_effective_exclude(&config.tree.exclude),
+                &builtin_ignores.tree,
+            )?,
+            source: SectionFilter::build(
+                config.get_effective_include(&config.source.include),
+                config.get_effective_exclude(&config.source.exclude),
+                &builtin_ignores.source,
+            )?,
+            docs: if docs_active {
+                SectionFilter::build(
+                    config.get_effective_include(&config.docs.include),
+                    config.get_effective_exclude(&config.docs.exclude),
+                    &builtin_ignores.docs,
+                )?
+            } else {
+                SectionFilter::disabled()
+            },
+            common_builtin_exclude: PatternSet::build(&builtin_ignores.common)?,
+            use_builtin: config.get_effective_builtin_ignore(),
+            docs_active,
+            pruner: DirPruner::build(config, builtin_ignores, docs_active)?,
+        })
+    }
+
+    // Gitignore filtering is handled by the WalkBuilder itself, so it is not part of this decision
+    fn decision(
+        &self,
+        relative_path: &Path,
+        is_dir: bool,
+        section: &SectionFilter,
+    ) -> FilterDecision {
+        // 1. Check Explicit Excludes
+        if let Some(pattern) = section.exclude.matching_pattern(relative_path, is_dir) {
             log::trace!(
-                "Path excluded by common built-in ignores: {}",
+                "Path excluded by explicit exclude set: {}",
                 relative_path.display()
             );
-            return false;
+            return FilterDecision::ExcludedByPattern(pattern.to_string());
         }
-        if section_builtin_exclude.is_match(relative_path)
-            || (is_dir
-                && section_builtin_exclude.is_match(relative_path.join("dummy_file_for_dir_match")))
+
+        // 2. Check Explicit Includes (if any were provided)
+        if section.has_includes
+            && section
+                .include
+                .matching_pattern(relative_path, is_dir)
+                .is_none()
         {
             log::trace!(
-                "Path excluded by section built-in ignores: {}",
+                "Path not included by explicit include set: {}",
                 relative_path.display()
             );
-            return false;
+            return FilterDecision::NotMatchedByIncludes;
+        }
+
+        // 3. Check Built-in Ignores
+        if self.use_builtin {
+            if let Some(pattern) = self
+                .common_builtin_exclude
+                .matching_pattern(relative_path, is_dir)
+            {
+                log::trace!(
+                    "Path excluded by common built-in ignores: {}",
+                    relative_path.display()
+                );
+                return FilterDecision::ExcludedByCommonBuiltin(pattern.to_string());
+            }
+            if let Some(pattern) = section
+                .builtin_exclude
+                .matching_pattern(relative_path, is_dir)
+            {
+                log::trace!(
+                    "Path excluded by section built-in ignores: {}",
+                    relative_path.display()
+                );
+                return FilterDecision::ExcludedBySectionBuiltin(pattern.to_string());
+            }
         }
+
+        // If not excluded by any rule, include it
+        log::trace!("Path included: {}", relative_path.display());
+        FilterDecision::Included
     }
+}
 
-    // If not excluded by any rule, include it
-    log::trace!("Path included: {}", relative_path.display());
-    true
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize))]
+#[cfg_attr(
+    feature = "serde_support",
+    serde(tag = "decision", content = "pattern", rename_all = "snake_case")
+)]
+pub enum FilterDecision {
+    Included,
+    ExcludedByPattern(String),
+    NotMatchedByIncludes,
+    ExcludedByCommonBuiltin(String),
+    ExcludedBySectionBuiltin(String),
 }
 
-pub fn build_tree_from_paths(relative_path_types: &[(String, bool)]) -> Result<Vec<TreeNode>> {
+impl FilterDecision {
+    pub fn is_included(&self) -> bool {
+        matches!(self, FilterDecision::Included)
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            FilterDecision::Included => "included".to_string(),
+            FilterDecision::ExcludedByPattern(p) => format!("excluded by pattern '{}'", p),
+            FilterDecision::NotMatchedByIncludes => {
+                "not matched by any include pattern".to_string()
+            }
+            FilterDecision::ExcludedByCommonBuiltin(p) => {
+                format!("excluded by common built-in ignore '{}'", p)
+            }
+            FilterDecision::ExcludedBySectionBuiltin(p) => {
+                format!("excluded by section built-in ignore '{}'", p)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde_support", derive(Serialize))]
+pub struct PathExplanation {
+    pub path: String,
+    pub exists: bool,
+    pub is_dir: bool,
+    /// Set when the walker never yields the path (e.g. `.git` or the cache directory).
+    pub skipped_internally: Option<String>,
+    /// Matching ignore rule as `file:line: pattern`, when gitignore filtering is enabled.
+    pub gitignore: Option<String>,
+    pub tree: FilterDecision,
+    pub source: FilterDecision,
+    pub docs: Option<FilterDecision>, // None when the docs section is inactive
+    pub sections: Vec<String>,
+}
+
+/// Explains, step by step, why a path does or does not end up in the generated context.
+pub fn explain_path(
+    project_root: &Path,
+    config: &Config,
+    relative_path: &Path,
+) -> Result<PathExplanation> {
+    let absolute_path = project_root.join(relative_path);
+    let exists = absolute_path.exists();
+    let is_dir = absolute_path.is_dir();
+    let filters = FilterSets::from_config(config)?;
+
+    let skipped_internally = if relative_path.starts_with(".git") {
+        Some("paths inside .git are always skipped".to_string())
+    } else if relative_path.starts_with(crate::config::DEFAULT_CACHE_DIR) {
+        Some(format!(
+            "paths inside the cache directory '{}' are always skipped",
+            crate::config::DEFAULT_CACHE_DIR
+        ))
+    } else {
+        None
+    };
+
+    let gitignore = if config.general.use_gitignore {
+        find_gitignore_match(project_root, relative_path, is_dir)
+    } else {
+        None
+    };
+
+    let tree = filters.decision(relative_path, is_dir, &filters.tree);
+    let source = filters.decision(relative_path, false, &filters.source);
+    let docs = filters
+        .docs_active
+        .then(|| filters.decision(relative_path, false, &filters.docs));
+
+    let mut sections = Vec::new();
+    if skipped_internally.is_none() && gitignore.is_none() {
+        if config.tree.enabled && tree.is_included() {
+            sections.push("tree".to_string());
+        }
+        let in_docs = !is_dir && docs.as_ref().is_some_and(FilterDecision::is_included);
+        if in_docs {
+            sections.push("docs".to_string());
+        } else if !is_dir && config.source.enabled && source.is_included() {
+            sections.push("source".to_string());
+        }
+    }
+
+    Ok(PathExplanation {
+        path: relative_path.to_string_lossy().to_string(),
+        exists,
+        is_dir,
+        skipped_internally,
+        gitignore,
+        tree,
+        source,
+        docs,
+        sections,
+    })
+}
+
+// Mirrors the walker's ignore handling: the deepest directory with a matching rule decides.
+fn find_gitignore_match(project_root: &Path, relative_path: &Path, is_dir: bool) -> Option<String> {
+    let absolute_path = project_root.join(relative_path);
+    let mut dirs: Vec<PathBuf> = relative_path
+        .ancestors()
+        .skip(1)
+        .map(|ancestor| project_root.join(ancestor))
+        .collect();
+    dirs.sort_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+
+    for dir in dirs {
+        let mut candidates = vec![dir.join(".ignore"), dir.join(".gitignore")];
+        if dir == project_root {
+            candidates.push(dir.join(".git").join("info").join("exclude"));
+        }
+        for ignore_file in candidates.into_iter().filter(|f| f.is_file()) {
+            let mut builder = ignore::gitignore::GitignoreBuilder::new(&dir);
+            if let Some(err) = builder.add(&ignore_file) {
+                log::warn!("Failed to parse {}: {}", ignore_file.display(), err);
+                continue;
+            }
+            let Ok(gitignore) = builder.build() else {
+                continue;
+            };
+            match gitignore.matched_path_or_any_parents(&absolute_path, is_dir) {
+                ignore::Match::Ignore(glob) => {
+                    return Some(describe_ignore_glob(project_root, glob, "ignored"));
+                }
+                ignore::Match::Whitelist(glob) => {
+                    log::debug!(
+                        "{}",
+                        describe_ignore_glob(project_root, glob, "re-included")
+                    );
+                    return None;
+                }
+                ignore::Match::None => {}
+            }
+        }
+    }
+    None
+}
+
+fn describe_ignore_glob(project_root: &Path, glob: &ignore::gitignore::Glob, verb: &str) -> String {
+    let origin = glob
+        .from()
+        .and_then(|f| pathdiff::diff_paths(f, project_root))
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_else(|| "<unknown>".to_string());
+    format!("{} by '{}' in {}", verb, glob.original(), origin)
+}
+
+pub fn build_tree_from_paths(
+    relative_path_types: &[(String, bool)],
+    tree_config: &TreeConfig,
+) -> Result<Vec<TreeNode>> {
     log::debug!(
         "Building tree structure from {} paths...",
         relative_path_types.len()
@@ -451,10 +1362,62 @@ pub fn build_tree_from_paths(relative_path_types: &[(String, bool)]) -> Result<V
 
     // Sort root nodes alphabetically
     root_nodes.sort_by(|a, b| a.name.cmp(&b.name));
+    // Insertion relies on alphabetical order (binary search), so grouping happens afterwards
+    if tree_config.dirs_first {
+        sort_dirs_first(&mut root_nodes);
+    }
+    if !tree_config.collapse.is_empty() {
+        let collapse_set = build_glob_set_from_vec(&tree_config.collapse)?;
+        mark_collapsed_dirs(&mut root_nodes, Path::new(""), &collapse_set);
+    }
+    if tree_config.prune_empty_dirs {
+        prune_empty_dirs(&mut root_nodes);
+    }
     log::debug!("Tree structure built successfully.");
     Ok(root_nodes)
 }
 
+fn mark_collapsed_dirs(nodes: &mut [TreeNode], parent: &Path, collapse_set: &GlobSet) {
+    for node in nodes.iter_mut().filter(|n| n.node_type == "directory") {
+        let path = parent.join(&node.name);
+        if collapse_set.is_match(&path)
+            || collapse_set.is_match(path.join("dummy_file_for_dir_match"))
+        {
+            node.collapsed = true;
+            node.children = None;
+        } else if let Some(children) = node.children.as_mut() {
+            mark_collapsed_dirs(children, &path, collapse_set);
+        }
+    }
+}
+
+// Drops directories with no file anywhere below them. Collapsed directories are kept: their
+// contents were deliberately not walked, so emptiness is unknown.
+fn prune_empty_dirs(nodes: &mut Vec<TreeNode>) {
+    nodes.retain_mut(|node| {
+        if node.node_type != "directory" || node.collapsed {
+            return true;
+        }
+        match node.children.as_mut() {
+            Some(children) => {
+                prune_empty_dirs(children);
+                !children.is_empty()
+            }
+            None => false,
+        }
+    });
+}
+
+// Stable sort keeps the alphabetical order within the directory and file groups
+fn sort_dirs_first(nodes: &mut [TreeNode]) {
+    nodes.sort_by_key(|node| node.node_type != "directory");
+    for node in nodes.iter_mut() {
+        if let Some(children) = node.children.as_mut() {
+            sort_dirs_first(children);
+        }
+    }
+}
+
 fn insert_node(
     current_level_nodes: &mut Vec<TreeNode>,
     components: &[String],
@@ -526,6 +1489,8 @@ fn insert_node(
                 } else {
                     None
                 },
+                readme: None,
+                collapsed: false,
             };
 
             if !is_last_component {
@@ -548,3 +1513,103 @@ fn insert_node(
     }
     Ok(())
 }
+
+pub fn attach_directory_readmes(nodes: &mut [TreeNode], project_root: &Path) {
+    log::debug!("Attaching directory README files to tree nodes...");
+    attach_readmes_recursive(nodes, project_root);
+}
+
+fn attach_readmes_recursive(nodes: &mut [TreeNode], current_dir: &Path) {
+    for node in nodes.iter_mut() {
+        if node.node_type != "directory" {
+            continue;
+        }
+        let dir_path = current_dir.join(&node.name);
+        node.readme = DIRECTORY_README_NAMES.iter().find_map(|readme_name| {
+            let readme_path = dir_path.join(readme_name);
+            if !readme_path.is_file() {
+                return None;
+            }
+            match fs::read_to_string(&readme_path) {
+                Ok(content) if !content.trim().is_empty() => {
+                    log::trace!("Attached README: {}", readme_path.display());
+                    Some(content)
+                }
+                Ok(_) => None,
+                Err(e) => {
+                    log::warn!(
+                        "Failed to read directory README '{}': {}",
+                        readme_path.display(),
+                        e
+                    );
+                    None
+                }
+            }
+        });
+        if let Some(children) = node.children.as_mut() {
+            attach_readmes_recursive(children, &dir_path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_error(pattern: &str) -> String {
+        match build_glob_set_from_vec(&[pattern.to_string()]) {
+            Ok(_) => panic!("pattern {:?} should not compile", pattern),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn glob_error_hint_matrix() {
+        let cases: &[(&str, Option<&str>)] = &[
+            ("src/*.{rs,toml", Some("without a matching '}'")),
+            ("src/*.rs}", Some("'}' without a matching '{'")),
+            ("}{", Some("'}' without a matching '{'")),
+            ("src\\lib.rs", Some("use '/' as the path separator")),
+            ("src/[ab.txt", Some("need a closing ']'")),
+            ("src/**/*.rs", None),
+            ("*.{rs,toml}", None),
+        ];
+        for (pattern, expected) in cases {
+            let hint = glob_error_hint(pattern);
+            match expected {
+                Some(fragment) => assert!(
+                    hint.is_some_and(|h| h.contains(fragment)),
+                    "{:?}: expected hint containing {:?}, got {:?}",
+                    pattern,
+                    fragment,
+                    hint
+                ),
+                None => assert_eq!(hint, None, "{:?} should have no hint", pattern),
+            }
+        }
+    }
+
+    #[test]
+    fn build_errors_carry_hints() {
+        let cases = [
+            ("src/*.{rs,toml", "without a matching '}'"),
+            ("src/[ab.txt", "need a closing ']'"),
+            ("src\\", "use '/' as the path separator"),
+        ];
+        for (pattern, fragment) in cases {
+            let message = build_error(pattern);
+            assert!(
+                message.contains("Hint:") && message.contains(fragment),
+                "{:?}: unexpected error {:?}",
+                pattern,
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn valid_patterns_build() {
+        let patterns = ["target/", "src/**/*.rs", "*.{rs,toml}"].map(String::from);
+        assert!(build_glob_set_from_vec(&patterns).is_ok());
+    }
+}
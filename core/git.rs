@@ -0,0 +1,60 @@
+use crate::error::{AppError, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// Resolves the repository's default branch from the 'origin/HEAD' symbolic ref, if one is set
+pub fn detect_default_branch(project_root: &Path) -> Option<String> {
+    let git_dir = find_common_git_dir(project_root)?;
+    let head = fs::read_to_string(git_dir.join("refs/remotes/origin/HEAD")).ok()?;
+    let branch = head
+        .trim()
+        .strip_prefix("ref: refs/remotes/origin/")?
+        .to_string();
+    log::debug!("Detected default branch: {}", branch);
+    (!branch.is_empty()).then_some(branch)
+}
+
+// Walks up to the nearest '.git', following the 'gitdir:' and 'commondir' indirections used by
+// linked worktrees so remote refs are read from the main repository
+fn find_common_git_dir(start: &Path) -> Option<PathBuf> {
+    let dot_git = start
+        .ancestors()
+        .map(|dir| dir.join(".git"))
+        .find(|candidate| candidate.exists())?;
+    let git_dir = if dot_git.is_file() {
+        let content = fs::read_to_string(&dot_git).ok()?;
+        let target = content.trim().strip_prefix("gitdir:")?.trim();
+        dot_git.parent()?.join(target)
+    } else {
+        dot_git
+    };
+    match fs::read_to_string(git_dir.join("commondir")) {
+        Ok(common) => Some(git_dir.join(common.trim())),
+        Err(_) => Some(git_dir),
+    }
+}
+
+/// Lists files git reports as untracked (and not ignored) under `project_root`, as absolute paths.
+pub fn list_untracked_files(project_root: &Path) -> Result<HashSet<PathBuf>> {
+    let output = Command::new("git")
+        .args(["ls-files", "--others", "--exclude-standard", "-z"])
+        .current_dir(project_root)
+        .output()
+        .map_err(|e| AppError::Git(format!("Failed to run git: {}", e)))?;
+    if !output.status.success() {
+        return Err(AppError::Git(format!(
+            "Listing untracked files requires a git repository ({}): {}",
+            project_root.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    // Paths are relative to the working directory, which is the project root
+    Ok(output
+        .stdout
+        .split(|&b| b == 0)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| project_root.join(String::from_utf8_lossy(entry).as_ref()))
+        .collect())
+}
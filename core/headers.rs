@@ -0,0 +1,103 @@
+use std::path::Path;
+
+const MAX_SUMMARY_CHARS: usize = 400;
+
+// Extensions whose line comments start with '#'; everything else is treated as C-style.
+pub(crate) const HASH_COMMENT_EXTENSIONS: &[&str] = &[
+    "py", "pyi", "rb", "rake", "sh", "bash", "zsh", "fish", "pl", "r", "toml", "yaml", "yml", "ex",
+    "exs", "nix",
+];
+
+// Heuristically extracts the leading doc comment or comment block of a file as a one-line summary:
+// `//!` then `///` for Rust, a module docstring for Python, otherwise the first block or run of
+// line comments at the top of the file.
+pub fn extract_header_summary(path: &Path, content: &str) -> Option<String> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    let lines: Vec<&str> = content
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty() || line.starts_with("#!"))
+        .collect();
+
+    let summary = match extension.as_str() {
+        "rs" => leading_line_comments(&lines, "//!")
+            .or_else(|| leading_line_comments(&lines, "///"))
+            .or_else(|| leading_block_comment(&lines))
+            .or_else(|| leading_line_comments(&lines, "//")),
+        "py" | "pyi" => python_docstring(&lines).or_else(|| leading_line_comments(&lines, "#")),
+        ext if HASH_COMMENT_EXTENSIONS.contains(&ext) => leading_line_comments(&lines, "#"),
+        _ => leading_block_comment(&lines).or_else(|| leading_line_comments(&lines, "//")),
+    }?;
+    Some(truncate_summary(summary))
+}
+
+fn leading_line_comments(lines: &[&str], marker: &str) -> Option<String> {
+    let text: Vec<&str> = lines
+        .iter()
+        .take_while(|line| line.starts_with(marker))
+        .map(|line| {
+            line[marker.len()..]
+                .trim_start_matches(['/', '!', '#'])
+                .trim()
+        })
+        .filter(|line| !line.is_empty())
+        .collect();
+    (!text.is_empty()).then(|| text.join(" "))
+}
+
+fn leading_block_comment(lines: &[&str]) -> Option<String> {
+    let first = lines.first()?.strip_prefix("/*")?;
+    let mut text = Vec::new();
+    for line in std::iter::once(first).chain(lines[1..].iter().copied()) {
+        let (body, closed) = match line.find("*/") {
+            Some(end) => (&line[..end], true),
+            None => (line, false),
+        };
+        let body = body.trim_start_matches(['*', '!']).trim();
+        if !body.is_empty() {
+            text.push(body);
+        }
+        if closed {
+            break;
+        }
+    }
+    (!text.is_empty()).then(|| text.join(" "))
+}
+
+fn python_docstring(lines: &[&str]) -> Option<String> {
+    let lines: Vec<&str> = lines
+        .iter()
+        .copied()
+        .skip_while(|line| line.starts_with('#') || line.is_empty())
+        .collect();
+    let first = lines.first()?;
+    let quote = ["\"\"\"", "'''"]
+        .into_iter()
+        .find(|q| first.starts_with(q))?;
+    let first = &first[quote.len()..];
+    let mut text = Vec::new();
+    for line in std::iter::once(first).chain(lines[1..].iter().copied()) {
+        let (body, closed) = match line.find(quote) {
+            Some(end) => (&line[..end], true),
+            None => (line, false),
+        };
+        if !body.trim().is_empty() {
+            text.push(body.trim());
+        }
+        if closed {
+            break;
+        }
+    }
+    (!text.is_empty()).then(|| text.join(" "))
+}
+
+fn truncate_summary(summary: String) -> String {
+    match summary.char_indices().nth(MAX_SUMMARY_CHARS) {
+        Some((cut, _)) => format!("{}...", &summary[..cut]),
+        None => summary,
+    }
+}
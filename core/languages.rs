@@ -0,0 +1,61 @@
+use std::path::Path;
+
+// Maps file extensions (lowercase) to Markdown fenced code block language ids.
+// Used by the human-readable output formats so code renders with syntax highlighting.
+pub fn markdown_language_for_extension(extension: &str) -> Option<&'static str> {
+    match extension.to_lowercase().as_str() {
+        "rs" => Some("rust"),
+        "py" | "pyi" => Some("python"),
+        "rb" | "rake" | "gemspec" => Some("ruby"),
+        "js" | "cjs" | "mjs" => Some("javascript"),
+        "jsx" => Some("jsx"),
+        "ts" | "mts" | "cts" => Some("typescript"),
+        "tsx" => Some("tsx"),
+        "go" => Some("go"),
+        "c" | "h" => Some("c"),
+        "cpp" | "cc" | "cxx" | "hpp" | "hh" | "hxx" => Some("cpp"),
+        "cs" => Some("csharp"),
+        "java" => Some("java"),
+        "kt" | "kts" => Some("kotlin"),
+        "swift" => Some("swift"),
+        "php" => Some("php"),
+        "lua" => Some("lua"),
+        "sh" | "bash" | "zsh" => Some("bash"),
+        "fish" => Some("fish"),
+        "ps1" => Some("powershell"),
+        "sql" => Some("sql"),
+        "html" | "htm" => Some("html"),
+        "css" => Some("css"),
+        "scss" => Some("scss"),
+        "json" => Some("json"),
+        "yaml" | "yml" => Some("yaml"),
+        "toml" => Some("toml"),
+        "xml" => Some("xml"),
+        "md" | "markdown" => Some("markdown"),
+        "org" => Some("org"),
+        "el" => Some("elisp"),
+        "ex" | "exs" => Some("elixir"),
+        "hs" => Some("haskell"),
+        "scala" => Some("scala"),
+        "dart" => Some("dart"),
+        "zig" => Some("zig"),
+        "nix" => Some("nix"),
+        "proto" => Some("protobuf"),
+        _ => None, // Unknown extension: emit a bare fence
+    }
+}
+
+// Resolves the language id for a path, checking well-known extensionless filenames first.
+pub fn markdown_language_for_path(path: &str) -> Option<&'static str> {
+    let path = Path::new(path);
+    let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    match filename {
+        "Rakefile" | "Gemfile" => return Some("ruby"),
+        "Makefile" | "makefile" | "GNUmakefile" => return Some("makefile"),
+        "Dockerfile" => return Some("dockerfile"),
+        _ => {}
+    }
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(markdown_language_for_extension)
+}
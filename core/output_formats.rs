@@ -1,8 +1,12 @@
+use crate::context::ProjectContext;
 use crate::error::{AppError, Result};
+use indexmap::IndexMap;
 use once_cell::sync::Lazy;
 #[cfg(feature = "serde_support")]
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::path::Path;
 
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
@@ -10,6 +14,153 @@ use std::collections::HashMap;
 pub struct FileContextInfo {
     pub path: String,
     pub content: String,
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub note: Option<String>,
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub hash: Option<String>, // SHA-256 of `content`, lowercase hex
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub package: Option<String>, // Owning workspace member, with output.include_workspaces
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(rename = "type", default, skip_serializing_if = "Option::is_none")
+    )]
+    pub doc_type: Option<String>, // Docs only, with docs.content_type; see classify_doc_type
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub mode: Option<String>, // Octal st_mode, e.g. "100755", with output.include_file_mode (Unix only)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize))]
+#[cfg_attr(feature = "serde_support", serde(rename_all = "camelCase"))]
+pub struct GitInfo {
+    pub default_branch: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize))]
+pub struct PromptEntry {
+    pub name: String,
+    pub text: String,
+}
+
+// Serialized either as a name -> text object or, with output.prompts_as_list, as an array
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize))]
+#[cfg_attr(feature = "serde_support", serde(untagged))]
+pub enum PromptsSection {
+    Map(IndexMap<String, String>),
+    List(Vec<PromptEntry>),
+}
+
+impl PromptsSection {
+    pub fn new(prompts: IndexMap<String, String>, as_list: bool) -> Self {
+        if !as_list {
+            return Self::Map(prompts);
+        }
+        let mut entries: Vec<PromptEntry> = prompts
+            .into_iter()
+            .map(|(name, text)| PromptEntry { name, text })
+            .collect();
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Self::List(entries)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize))]
+#[cfg_attr(feature = "serde_support", serde(rename_all = "camelCase"))]
+pub struct FileOverview {
+    pub path: String,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde_support", derive(Serialize))]
+#[cfg_attr(feature = "serde_support", serde(rename_all = "camelCase"))]
+pub struct MetricsSummary {
+    pub total_files: usize,
+    pub total_lines: usize,
+    pub total_bytes: usize,
+    pub estimated_tokens: usize,
+    pub by_extension: IndexMap<String, ExtensionMetrics>, // Sorted by extension
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde_support", derive(Serialize))]
+#[cfg_attr(feature = "serde_support", serde(rename_all = "camelCase"))]
+pub struct ExtensionMetrics {
+    pub files: usize,
+    pub lines: usize,
+    pub bytes: usize,
+    pub estimated_tokens: usize,
+}
+
+// Content-free fingerprint of a context: enough to tell whether anything changed since last run
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde_support", derive(Serialize))]
+#[cfg_attr(feature = "serde_support", serde(rename_all = "camelCase"))]
+pub struct ContentManifest {
+    pub digest: String, // SHA-256 over every entry's path and hash, in order
+    pub total_bytes: usize,
+    pub files: Vec<ContentManifestEntry>, // Sorted by path
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize))]
+#[cfg_attr(feature = "serde_support", serde(rename_all = "camelCase"))]
+pub struct ContentManifestEntry {
+    pub path: String,
+    pub section: String, // "source" or "docs"
+    pub size: usize,
+    pub hash: String,
+}
+
+impl ContentManifest {
+    pub fn from_files(
+        source_files: &[crate::gather::FileInfo],
+        docs_files: &[crate::gather::FileInfo],
+        project_root: &Path,
+        posix_paths: bool,
+    ) -> Self {
+        let sections = [("source", source_files), ("docs", docs_files)];
+        let mut files: Vec<ContentManifestEntry> = sections
+            .into_iter()
+            .flat_map(|(section, infos)| {
+                infos.iter().map(move |finfo| ContentManifestEntry {
+                    path: relative_output_path(&finfo.path, project_root, posix_paths),
+                    section: section.to_string(),
+                    size: finfo.size,
+                    hash: content_hash(&finfo.content),
+                })
+            })
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut hasher = Sha256::new();
+        for entry in &files {
+            hasher.update(entry.path.as_bytes());
+            hasher.update([0]);
+            hasher.update(entry.hash.as_bytes());
+            hasher.update([b'\n']);
+        }
+        Self {
+            digest: format!("{:x}", hasher.finalize()),
+            total_bytes: files.iter().map(|entry| entry.size).sum(),
+            files,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Default)]
@@ -26,10 +177,15 @@ pub struct SourceRepresentation {
         serde(skip_serializing_if = "Option::is_none")
     )]
     pub chunks: Option<Vec<String>>,
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub omitted_per_dir: Option<IndexMap<String, usize>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde_support", derive(Serialize))]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde_support", serde(rename_all = "camelCase"))]
 pub struct ChunkInfo {
     pub current_part: usize,
@@ -37,7 +193,7 @@ pub struct ChunkInfo {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde_support", derive(Serialize))]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde_support", serde(rename_all = "camelCase"))]
 pub struct ChunkFile {
     pub files: Vec<FileContextInfo>,
@@ -48,18 +204,29 @@ pub struct ChunkFile {
 #[cfg_attr(feature = "serde_support", derive(Deserialize))]
 pub struct AiReadmeText {
     pub intro: String,
+    pub ai_readme_desc: String,
+    pub legend_desc: String,
     pub key_sections_header: String,
     pub project_name_desc: String,
     pub project_root_desc: String,
     pub system_info_desc: String,
     pub meta_desc: String,
+    pub file_index_desc: String,
+    pub overview_desc: String,
+    pub metrics_desc: String,
     pub docs_desc: String,
+    pub docs_omitted_desc: String,
     pub tree_desc: String,
+    pub workspaces_desc: String,
+    pub git_info_desc: String,
+    pub dependencies_desc: String,
+    pub editorconfig_desc: String,
     pub source_files_desc: String,
     pub source_chunks_desc: String,
     pub source_missing_desc: String,
     pub rules_desc: String,
     pub rules_missing_desc: String,
+    pub config_desc: String,
     pub timestamp_desc: String,
 }
 #[derive(Debug, Default)]
@@ -107,6 +274,23 @@ pub fn get_builtin_ignore_patterns() -> &'static BuiltinIgnores {
     &BUILTIN_IGNORE_PATTERNS
 }
 
+// Stable identifier for emitted file content, so consumers can skip unchanged files
+pub fn content_hash(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+// Renders `path` relative to `base` (falling back to the path itself) for emitted output.
+// With `posix_paths`, Windows separators are normalized to '/' so output is platform-independent.
+pub fn relative_output_path(path: &Path, base: &Path, posix_paths: bool) -> String {
+    let relative = pathdiff::diff_paths(path, base).unwrap_or_else(|| path.to_path_buf());
+    let path_str = relative.to_string_lossy().to_string();
+    if posix_paths && std::path::MAIN_SEPARATOR == '\\' {
+        path_str.replace('\\', "/")
+    } else {
+        path_str
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TextType {
     Prompt,
@@ -141,46 +325,453 @@ pub fn serialize_context_to_json<T: Serialize>(
     }
 }
 
+// Prompt-ready text: project name, meta and rules as a preamble, then docs and source files as
+// numbered `<document>` blocks. File contents are emitted verbatim, not XML-escaped.
+pub fn serialize_context_to_doc_tags(context: &ProjectContext) -> String {
+    let mut out = String::new();
+    if let Some(config) = &context.config {
+        // Doc-tags has no structured slot for the config, so it leads as a TOML comment block
+        match toml::to_string(config) {
+            Ok(config_toml) => {
+                out.push_str("<!-- xcontext config\n");
+                out.push_str(&config_toml.replace("-->", "-- >"));
+                out.push_str("-->\n");
+            }
+            Err(e) => log::warn!("Could not embed config as TOML: {}", e),
+        }
+    }
+    out.push_str("<project_context>\n");
+    if let Some(name) = &context.project_name {
+        out.push_str(&format!("<project_name>{}</project_name>\n", name));
+    }
+    if let Some(meta) = context.meta.as_ref().filter(|m| !m.is_empty()) {
+        let mut keys: Vec<&String> = meta.keys().collect();
+        keys.sort();
+        out.push_str("<meta>\n");
+        for key in keys {
+            out.push_str(&format!("{}: {}\n", key, meta[key]));
+        }
+        out.push_str("</meta>\n");
+    }
+    if !context.rules.is_empty() {
+        out.push_str("<rules>\n");
+        for (name, rules) in &context.rules {
+            out.push_str(&format!("<ruleset name=\"{}\">\n", name));
+            for rule in rules {
+                out.push_str(rule);
+                out.push('\n');
+            }
+            out.push_str("</ruleset>\n");
+        }
+        out.push_str("</rules>\n");
+    }
+    out.push_str("</project_context>\n");
+
+    let docs = context.docs.iter().flatten();
+    let sources = context
+        .source
+        .as_ref()
+        .and_then(|s| s.files.as_ref())
+        .into_iter()
+        .flatten();
+    out.push_str("<documents>\n");
+    for (index, file) in docs.chain(sources).enumerate() {
+        out.push_str(&format!("<document index=\"{}\">\n", index + 1));
+        out.push_str(&format!("<source>{}</source>\n", file.path));
+        if let Some(note) = &file.note {
+            out.push_str(&format!("<note>{}</note>\n", note));
+        }
+        out.push_str("<document_contents>\n");
+        out.push_str(&file.content);
+        if !file.content.ends_with('\n') {
+            out.push('\n');
+        }
+        out.push_str("</document_contents>\n</document>\n");
+    }
+    out.push_str("</documents>");
+    out
+}
+
+// Readable Markdown for chat UIs; not meant to round-trip. Meta, rules and dependencies become
+// bullet lists, metrics a table, the tree a fenced block, and every file or prompt a `### <name>`
+// heading over a fence. `aiReadme` and `legend` explain the JSON layout, so they are left out.
+pub fn serialize_context_to_markdown(context: &ProjectContext) -> String {
+    let title = context.project_name.as_deref().unwrap_or("Project context");
+    let mut out = format!("# {}\n", title);
+    if let Some(root) = &context.project_root {
+        out.push_str(&format!("\nRoot: `{}`\n", root));
+    }
+    if let Some(meta) = context.meta.as_ref().filter(|m| !m.is_empty()) {
+        let mut keys: Vec<&String> = meta.keys().collect();
+        keys.sort();
+        out.push_str("\n## Meta\n\n");
+        for key in keys {
+            out.push_str(&format!("- **{}**: {}\n", key, meta[key]));
+        }
+    }
+    if let Some(timestamp) = &context.generation_timestamp {
+        out.push_str(&format!("\nGenerated: {}\n", timestamp.to_rfc3339()));
+    }
+    if let Some(serde_json::Value::Object(info)) = context
+        .system_info
+        .as_ref()
+        .and_then(|info| serde_json::to_value(info).ok())
+    {
+        out.push_str("\n## System\n\n");
+        for (key, value) in info {
+            let value = value
+                .as_str()
+                .map_or_else(|| value.to_string(), str::to_string);
+            out.push_str(&format!("- **{}**: {}\n", key, value));
+        }
+    }
+    if let Some(git) = &context.git_info {
+        out.push_str(&format!("\nDefault branch: `{}`\n", git.default_branch));
+    }
+    if let Some(metrics) = &context.metrics {
+        push_markdown_metrics(&mut out, metrics);
+    }
+    if let Some(overview) = context.overview.as_deref().filter(|o| !o.is_empty()) {
+        out.push_str("\n## Overview\n\n");
+        for entry in overview {
+            out.push_str(&format!("- `{}`: {}\n", entry.path, entry.summary));
+        }
+    }
+    if let Some(tree) = context.tree.as_deref().filter(|t| !t.is_empty()) {
+        out.push_str("\n## Tree\n\n");
+        push_fenced(&mut out, "text", &render_tree(tree, &str::to_string));
+    }
+    if let Some(index) = context.file_index.as_deref().filter(|i| !i.is_empty()) {
+        out.push_str("\n## File index\n\n");
+        for path in index {
+            out.push_str(&format!("- `{}`\n", path));
+        }
+    }
+    if let Some(workspaces) = context.workspaces.as_deref().filter(|w| !w.is_empty()) {
+        out.push_str("\n## Workspaces\n\n");
+        for member in workspaces {
+            out.push_str(&format!("- `{}`\n", member));
+        }
+    }
+    if let Some(manifests) = context.dependencies.as_deref().filter(|d| !d.is_empty()) {
+        out.push_str("\n## Dependencies\n");
+        for manifest in manifests {
+            out.push_str(&format!(
+                "\n### {} ({})\n\n",
+                manifest.manifest, manifest.ecosystem
+            ));
+            for (name, version) in &manifest.dependencies {
+                out.push_str(&format!("- {} `{}`\n", name, version));
+            }
+            for (name, version) in &manifest.dev_dependencies {
+                out.push_str(&format!("- {} `{}` (dev)\n", name, version));
+            }
+            for (name, version) in &manifest.peer_dependencies {
+                out.push_str(&format!("- {} `{}` (peer)\n", name, version));
+            }
+        }
+    }
+    if let Some(sections) = context.editorconfig.as_deref().filter(|e| !e.is_empty()) {
+        out.push_str("\n## Editorconfig\n\n");
+        for section in sections {
+            let settings: Vec<String> = section
+                .settings
+                .iter()
+                .map(|(key, value)| format!("{} = {}", key, value))
+                .collect();
+            out.push_str(&format!(
+                "- `[{}]`: {}\n",
+                section.glob,
+                settings.join(", ")
+            ));
+        }
+    }
+    if let Some(docs) = context.docs.as_deref().filter(|d| !d.is_empty()) {
+        out.push_str("\n## Docs\n");
+        for file in docs {
+            push_markdown_file(&mut out, file);
+        }
+    }
+    if let Some(omitted) = context.docs_omitted {
+        out.push_str(&format!(
+            "\n_{} more docs files were left out by docs.max_files._\n",
+            omitted
+        ));
+    }
+    if let Some(source) = &context.source {
+        match (&source.files, &source.chunks) {
+            (Some(files), _) if !files.is_empty() => {
+                out.push_str("\n## Source\n");
+                for file in files {
+                    push_markdown_file(&mut out, file);
+                }
+            }
+            (_, Some(chunks)) if !chunks.is_empty() => {
+                out.push_str("\n## Source\n\nChunked into:\n\n");
+                for chunk in chunks {
+                    out.push_str(&format!("- `{}`\n", chunk));
+                }
+            }
+            _ => {}
+        }
+    }
+    if !context.rules.is_empty() {
+        out.push_str("\n## Rules\n");
+        for (name, rules) in &context.rules {
+            out.push_str(&format!("\n### {}\n\n", name));
+            for rule in rules.iter().filter(|r| !r.trim().is_empty()) {
+                // Rule files usually carry their own bullet already
+                let rule = rule.trim_start().strip_prefix("- ").unwrap_or(rule);
+                out.push_str(&format!("- {}\n", rule));
+            }
+        }
+    }
+    let prompts: Vec<(&str, &str)> = match &context.prompts {
+        Some(PromptsSection::Map(map)) => map
+            .iter()
+            .map(|(name, text)| (name.as_str(), text.as_str()))
+            .collect(),
+        Some(PromptsSection::List(entries)) => entries
+            .iter()
+            .map(|e| (e.name.as_str(), e.text.as_str()))
+            .collect(),
+        None => Vec::new(),
+    };
+    if !prompts.is_empty() {
+        out.push_str("\n## Prompts\n");
+        for (name, text) in prompts {
+            out.push_str(&format!("\n### {}\n\n", name));
+            push_fenced(&mut out, "text", text);
+        }
+    }
+    if let Some(config) = &context.config {
+        match toml::to_string(config) {
+            Ok(config_toml) => {
+                out.push_str("\n## Config\n\n");
+                push_fenced(&mut out, "toml", &config_toml);
+            }
+            Err(e) => log::warn!("Could not embed config as TOML: {}", e),
+        }
+    }
+    out
+}
+
+fn push_markdown_metrics(out: &mut String, metrics: &MetricsSummary) {
+    out.push_str("\n## Metrics\n\n");
+    out.push_str(&format!(
+        "{} files, {} lines, {} bytes, ~{} tokens\n",
+        metrics.total_files, metrics.total_lines, metrics.total_bytes, metrics.estimated_tokens
+    ));
+    if metrics.by_extension.is_empty() {
+        return;
+    }
+    out.push_str("\n| Extension | Files | Lines | Bytes | Tokens |\n");
+    out.push_str("|---|---:|---:|---:|---:|\n");
+    for (extension, m) in &metrics.by_extension {
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            extension, m.files, m.lines, m.bytes, m.estimated_tokens
+        ));
+    }
+}
+
+/// Draws `nodes` with box connectors, one line per entry; `dir_label` styles each directory's
+/// "name/" (the terminal preview colors it, Markdown keeps it plain).
+pub fn render_tree(
+    nodes: &[crate::gather::TreeNode],
+    dir_label: &dyn Fn(&str) -> String,
+) -> String {
+    let mut out = String::new();
+    push_tree_lines(&mut out, nodes, "", dir_label);
+    out
+}
+
+fn push_tree_lines(
+    out: &mut String,
+    nodes: &[crate::gather::TreeNode],
+    prefix: &str,
+    dir_label: &dyn Fn(&str) -> String,
+) {
+    for (index, node) in nodes.iter().enumerate() {
+        let last = index + 1 == nodes.len();
+        let connector = if last { "└── " } else { "├── " };
+        if node.is_dir() {
+            let label = dir_label(&format!("{}/", node.name()));
+            out.push_str(&format!("{}{}{}\n", prefix, connector, label));
+            let child_prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+            push_tree_lines(out, node.children(), &child_prefix, dir_label);
+        } else {
+            out.push_str(&format!("{}{}{}\n", prefix, connector, node.name()));
+        }
+    }
+}
+
+fn push_markdown_file(out: &mut String, file: &FileContextInfo) {
+    out.push_str(&format!("\n### {}\n\n", file.path));
+    if let Some(note) = &file.note {
+        out.push_str(&format!("> {}\n\n", note));
+    }
+    let language = crate::languages::markdown_language_for_path(&file.path).unwrap_or("");
+    push_fenced(out, language, &file.content);
+}
+
+// The fence is one backtick longer than any run inside the content, so embedded fences survive
+fn push_fenced(out: &mut String, language: &str, content: &str) {
+    let longest_run = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat((longest_run + 1).max(3));
+    out.push_str(&format!("{}{}\n", fence, language));
+    out.push_str(content);
+    if !content.ends_with('\n') {
+        out.push('\n');
+    }
+    out.push_str(&format!("{}\n", fence));
+}
+
+// Markdown bundle of the docs alone: a table of contents built from each file's headings, then
+// every file in reading order (the root README first, then by path) under a `## <path>` header
+pub fn serialize_docs_bundle(
+    title: &str,
+    docs_files: &[crate::gather::FileInfo],
+    project_root: &Path,
+    posix_paths: bool,
+) -> String {
+    let mut docs: Vec<(String, &str)> = docs_files
+        .iter()
+        .map(|f| {
+            let path = relative_output_path(&f.path, project_root, posix_paths);
+            (path, f.content.as_str())
+        })
+        .collect();
+    docs.sort_by_cached_key(|(path, _)| (!is_root_readme(path), path.clone()));
+
+    let mut out = format!("# {} documentation\n\n## Contents\n\n", title);
+    for (path, content) in &docs {
+        out.push_str(&format!("- {}\n", path));
+        if is_markdown(path) {
+            for (level, heading) in markdown_headings(content) {
+                out.push_str(&format!("{}- {}\n", "  ".repeat(level), heading));
+            }
+        }
+    }
+    for (path, content) in &docs {
+        out.push_str(&format!("\n---\n\n## {}\n\n", path));
+        out.push_str(content);
+        if !content.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn is_root_readme(path: &str) -> bool {
+    !path.contains(['/', '\\']) && path.to_lowercase().starts_with("readme")
+}
+
+fn is_markdown(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    lower.ends_with(".md") || lower.ends_with(".markdown")
+}
+
+// ATX headings (`#` to `######`) outside fenced code blocks, as (level, text)
+fn markdown_headings(content: &str) -> Vec<(usize, &str)> {
+    let mut headings = Vec::new();
+    let mut fence: Option<&str> = None;
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if let Some(marker) = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m)) {
+            fence = match fence {
+                Some(open) if open == marker => None,
+                None => Some(marker),
+                other => other,
+            };
+            continue;
+        }
+        if fence.is_some() {
+            continue;
+        }
+        let level = trimmed.chars().take_while(|&c| c == '#').count();
+        let Some(text) = trimmed.get(level..).and_then(|rest| rest.strip_prefix(' ')) else {
+            continue;
+        };
+        let text = text.trim().trim_end_matches('#').trim();
+        if (1..=6).contains(&level) && !text.is_empty() {
+            headings.push((level, text));
+        }
+    }
+    headings
+}
+
+#[cfg(feature = "serde_support")]
+pub fn serialize_context_to_yaml<T: Serialize>(
+    context: &T,
+    pretty: bool,
+) -> Result<String, AppError> {
+    if pretty {
+        serde_yml::to_string(context).map_err(AppError::YamlError)
+    } else {
+        // Compact JSON is valid YAML flow style, which serde_yml cannot emit itself
+        serde_json::to_string(context).map_err(AppError::JsonSerialize)
+    }
+}
+
+// Writes each item as its own document of a single '---' separated YAML stream
 #[cfg(feature = "serde_support")]
-pub fn serialize_context_to_yaml<T: Serialize>(context: &T) -> Result<String, AppError> {
-    serde_yml::to_string(context).map_err(AppError::YamlError)
+pub fn serialize_yaml_documents<T: Serialize>(
+    documents: &[T],
+    pretty: bool,
+) -> Result<String, AppError> {
+    let mut out = String::new();
+    for document in documents {
+        let body = serialize_context_to_yaml(document, pretty)?;
+        out.push_str("---\n");
+        out.push_str(body.strip_prefix("---\n").unwrap_or(&body));
+        if !out.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+    Ok(out)
 }
 
 #[cfg(feature = "serde_support")]
 pub fn serialize_context_to_xml<T: Serialize>(
     context: &T,
     root_name: &str,
-    _pretty: bool, // Mark pretty as unused for now
+    namespace: Option<&str>,
+    pretty: bool,
 ) -> Result<String, AppError> {
-    // Use the simpler helper function which avoids manual Serializer creation
-    quick_xml::se::to_string_with_root(root_name, context)
-        .map_err(|e| AppError::XmlSerialize(e.to_string()))
-
-    // --- Keep the manual code commented out in case we need pretty printing later ---
-    /*
-    use quick_xml::se::Serializer;
-    use quick_xml::Writer;
-    use std::io::Cursor;
-
-    let mut buf = Vec::new();
-    // Create the writer wrapping the buffer
-    let mut writer = if pretty {
-        Writer::new_with_indent(Cursor::new(&mut buf), b' ', 4)
+    let mut buf = String::new();
+    if pretty {
+        let mut ser = quick_xml::se::Serializer::with_root(&mut buf, Some(root_name))
+            .map_err(|e| AppError::XmlSerialize(e.to_string()))?;
+        ser.indent(' ', 2);
+        context
+            .serialize(ser)
+            .map_err(|e| AppError::XmlSerialize(e.to_string()))?;
     } else {
-        Writer::new(Cursor::new(&mut buf))
-    };
+        buf = quick_xml::se::to_string_with_root(root_name, context)
+            .map_err(|e| AppError::XmlSerialize(e.to_string()))?;
+    }
+    match namespace {
+        Some(namespace) => add_xml_namespace(buf, root_name, namespace),
+        None => Ok(buf),
+    }
+}
 
-    // This block is likely causing the trait bound issue
-    { // Scope might help, but maybe not needed with to_string_with_root
-        let mut ser = Serializer::with_root(&mut writer, Some(root_name))?;
-        context.serialize(ser)?;
-    } // End of scope for ser
-
-    // Retrieve the buffer content
-    // NOTE: This might need adjustment depending on how `writer`'s state is managed
-    // after `ser` is dropped or consumed by `serialize`.
-    // Let's assume `buf` directly holds the data for now if not using the helper.
-    // let final_buf = writer.into_inner().into_inner().to_owned();
-    String::from_utf8(buf).map_err(|e| AppError::XmlSerialize(e.to_string()))
-    */
+// quick-xml cannot put attributes on the root it writes, so `xmlns` is spliced into the start tag
+#[cfg(feature = "serde_support")]
+fn add_xml_namespace(xml: String, root_name: &str, namespace: &str) -> Result<String, AppError> {
+    let tag_start = format!("<{}", root_name);
+    let after_name = tag_start.len();
+    if !xml.starts_with(&tag_start)
+        || !xml[after_name..].starts_with(|c: char| c == '>' || c == '/' || c.is_whitespace())
+    {
+        return Err(AppError::XmlSerialize(format!(
+            "Could not find the <{}> root element to add the namespace to",
+            root_name
+        )));
+    }
+    let attribute = format!(r#" xmlns="{}""#, quick_xml::escape::escape(namespace));
+    let mut out = xml;
+    out.insert_str(after_name, &attribute);
+    Ok(out)
 }
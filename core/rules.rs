@@ -1,7 +1,11 @@
 use crate::error::{AppError as Error, Result};
+use crate::output_formats::get_builtin_ignore_patterns;
+use globset::GlobBuilder;
+use ignore::WalkBuilder;
 use log;
 use rust_embed::RustEmbed; // Added use statement
 use std::collections::HashSet;
+use std::fs;
 use std::path::Path;
 use walkdir::WalkDir;
 
@@ -12,8 +16,17 @@ pub mod mapping; // Keep this declaration
 #[prefix = "rules/"] // Keep prefix for access path
 struct StaticRuleAssets;
 
+/// Loads an embedded rule by stem. Nested rules are addressed by subpath (`lang/rust`); a
+/// leading `rules/` and trailing `.org` are accepted too.
 pub fn get_static_rule_content(rule_stem: &str) -> Result<String> {
-    let file_path = format!("rules/{}.org", rule_stem);
+    let stem = normalize_static_rule_stem(rule_stem);
+    if stem.split('/').any(|part| part.is_empty() || part == "..") {
+        return Err(Error::RuleLoading(format!(
+            "Invalid static rule name: {}",
+            rule_stem
+        )));
+    }
+    let file_path = format!("rules/{}.org", stem);
     log::trace!("Attempting to get embedded static rule: {}", file_path);
     // Now StaticRuleAssets::get should work because RustEmbed trait is in scope
     let asset = StaticRuleAssets::get(&file_path).ok_or_else(|| {
@@ -28,6 +41,26 @@ pub fn get_static_rule_content(rule_stem: &str) -> Result<String> {
     Ok(content.to_string())
 }
 
+/// Strips an optional leading `rules/` and trailing `.org` from a static rule reference.
+pub fn normalize_static_rule_stem(rule_ref: &str) -> &str {
+    rule_ref
+        .trim_start_matches("rules/")
+        .trim_end_matches(".org")
+}
+
+/// Lists the stems of every embedded rule, sorted; nested rules appear as subpaths.
+pub fn list_static_rules() -> Vec<String> {
+    let mut stems: Vec<String> = StaticRuleAssets::iter()
+        .filter_map(|path| {
+            path.strip_prefix("rules/")?
+                .strip_suffix(".org")
+                .map(String::from)
+        })
+        .collect();
+    stems.sort();
+    stems
+}
+
 pub fn detect_project_characteristics(project_root: &Path) -> Result<HashSet<String>> {
     let mut characteristics = HashSet::new();
     log::debug!(
@@ -84,5 +117,120 @@ pub fn detect_project_characteristics(project_root: &Path) -> Result<HashSet<Str
     Ok(characteristics)
 }
 
+/// Lists workspace member directories declared by the Cargo, npm/yarn or pnpm manifest
+/// at the project root, as sorted relative paths.
+pub fn detect_workspace_members(project_root: &Path) -> Vec<String> {
+    let mut patterns: Vec<String> = Vec::new();
+
+    if let Ok(content) = fs::read_to_string(project_root.join("Cargo.toml")) {
+        match content.parse::<toml::Table>() {
+            Ok(manifest) => {
+                let members = manifest
+                    .get("workspace")
+                    .and_then(|w| w.get("members"))
+                    .and_then(|m| m.as_array());
+                patterns.extend(
+                    members
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|m| m.as_str().map(String::from)),
+                );
+            }
+            Err(e) => log::warn!("Could not parse Cargo.toml for workspaces: {}", e),
+        }
+    }
+
+    if let Ok(content) = fs::read_to_string(project_root.join("package.json")) {
+        match serde_json::from_str::<serde_json::Value>(&content) {
+            Ok(manifest) => {
+                // Either `"workspaces": [...]` or `"workspaces": { "packages": [...] }`
+                let workspaces = manifest.get("workspaces");
+                let members = workspaces
+                    .and_then(|w| w.as_array())
+                    .or_else(|| workspaces.and_then(|w| w.get("packages")?.as_array()));
+                patterns.extend(
+                    members
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|m| m.as_str().map(String::from)),
+                );
+            }
+            Err(e) => log::warn!("Could not parse package.json for workspaces: {}", e),
+        }
+    }
+
+    if let Ok(content) = fs::read_to_string(project_root.join("pnpm-workspace.yaml")) {
+        match serde_yml::from_str::<serde_yml::Value>(&content) {
+            Ok(manifest) => {
+                let members = manifest.get("packages").and_then(|p| p.as_sequence());
+                patterns.extend(
+                    members
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|m| m.as_str().map(String::from)),
+                );
+            }
+            Err(e) => log::warn!("Could not parse pnpm-workspace.yaml: {}", e),
+        }
+    }
+
+    let mut members: Vec<String> = patterns
+        .iter()
+        .filter(|p| !p.starts_with('!')) // Negated pnpm patterns only narrow the globs
+        .flat_map(|pattern| expand_workspace_pattern(project_root, pattern))
+        .collect();
+    members.sort();
+    members.dedup();
+    log::debug!("Detected workspace members: {:?}", members);
+    members
+}
+
+fn expand_workspace_pattern(project_root: &Path, pattern: &str) -> Vec<String> {
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    if !pattern.contains(['*', '?', '[', '{']) {
+        return if project_root.join(pattern).is_dir() {
+            vec![pattern.to_string()]
+        } else {
+            Vec::new()
+        };
+    }
+
+    let matcher = match GlobBuilder::new(pattern).literal_separator(true).build() {
+        Ok(glob) => glob.compile_matcher(),
+        Err(e) => {
+            log::warn!("Invalid workspace member pattern '{}': {}", pattern, e);
+            return Vec::new();
+        }
+    };
+    // '**' may match at any depth; otherwise members sit exactly one level per component
+    let max_depth = (!pattern.contains("**")).then(|| pattern.split('/').count());
+    // Like the main walk, honour ignore files and never descend into built-in ignored
+    // directories such as node_modules/, target/ or .git/, at any depth
+    let builtin_ignores = get_builtin_ignore_patterns();
+    let skipped_dirs: HashSet<&str> = builtin_ignores
+        .common
+        .iter()
+        .chain(&builtin_ignores.tree)
+        .filter_map(|p| p.trim().strip_suffix('/'))
+        .collect();
+    WalkBuilder::new(project_root)
+        .max_depth(max_depth)
+        .hidden(false)
+        .require_git(false)
+        .filter_entry(move |entry| {
+            !(entry.file_type().is_some_and(|ft| ft.is_dir())
+                && skipped_dirs.contains(entry.file_name().to_string_lossy().as_ref()))
+        })
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.depth() > 0 && entry.file_type().is_some_and(|ft| ft.is_dir()))
+        .filter_map(|entry| {
+            let relative = entry.path().strip_prefix(project_root).ok()?;
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            matcher.is_match(&relative).then_some(relative)
+        })
+        .collect()
+}
+
 // Removed the inline `pub mod mapping { ... }` block that started around line 84
 // The `pub mod mapping;` declaration at the top is sufficient.
@@ -0,0 +1,106 @@
+use crate::error::{AppError, Result};
+use once_cell::sync::Lazy;
+use tiktoken_rs::{CoreBPE, cl100k_base};
+
+// Loading the BPE tables is expensive, so the encoder is built once and shared
+static CL100K: Lazy<std::result::Result<CoreBPE, String>> =
+    Lazy::new(|| cl100k_base().map_err(|e| e.to_string()));
+
+/// Estimates the number of tokens in `text` using the cl100k_base encoding.
+pub fn estimate_tokens(text: &str) -> Result<usize> {
+    CL100K
+        .as_ref()
+        .map(|bpe| bpe.encode_ordinary(text).len())
+        .map_err(|e| AppError::TikToken(e.clone()))
+}
+
+/// Byte length of the longest token prefix of `text` that fits in `max_tokens` cl100k_base tokens
+/// and ends on a char boundary.
+pub fn token_prefix_len(text: &str, max_tokens: usize) -> Result<usize> {
+    let bpe = CL100K.as_ref().map_err(|e| AppError::TikToken(e.clone()))?;
+    let tokens = bpe.encode_ordinary(text);
+    if tokens.len() <= max_tokens {
+        return Ok(text.len());
+    }
+    // Ordinary tokens decode to exactly the bytes they were encoded from; a prefix ending inside a
+    // multi-byte char is not valid UTF-8, so tokens are dropped until one decodes
+    let mut count = max_tokens;
+    while count > 0 {
+        if let Ok(prefix) = bpe.decode(tokens[..count].to_vec()) {
+            return Ok(prefix.len());
+        }
+        count -= 1;
+    }
+    Ok(0)
+}
+
+// Rough average for English prose and code under BPE tokenizers
+const APPROX_BYTES_PER_TOKEN: usize = 4;
+
+/// Whether the cl100k_base tokenizer could be loaded.
+pub fn tokenizer_available() -> bool {
+    CL100K.is_ok()
+}
+
+/// Tokenizer-free estimate (bytes / 4, rounded up) for when cl100k_base is unavailable.
+pub fn approximate_tokens(text: &str) -> usize {
+    text.len().div_ceil(APPROX_BYTES_PER_TOKEN)
+}
+
+/// Counterpart of `token_prefix_len` for the bytes/4 estimate.
+pub fn approximate_prefix_len(text: &str, max_tokens: usize) -> usize {
+    let mut cut = max_tokens
+        .saturating_mul(APPROX_BYTES_PER_TOKEN)
+        .min(text.len());
+    while !text.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    cut
+}
+
+// Approximate list prices for input tokens (USD per 1K) used by `metrics --cost --model`.
+// Provider pricing changes; pass --price-per-1k for an exact rate.
+const MODEL_INPUT_PRICES_PER_1K: &[(&str, f64)] = &[
+    ("gpt-4o", 0.0025),
+    ("gpt-4o-mini", 0.00015),
+    ("gpt-4.1", 0.002),
+    ("gpt-4.1-mini", 0.0004),
+    ("claude-sonnet", 0.003),
+    ("claude-haiku", 0.0008),
+    ("claude-opus", 0.015),
+    ("gemini-1.5-pro", 0.00125),
+    ("gemini-1.5-flash", 0.000075),
+];
+
+/// Looks up the built-in input price (USD per 1K tokens) for `model`, case-insensitively.
+pub fn model_input_price_per_1k(model: &str) -> Option<f64> {
+    MODEL_INPUT_PRICES_PER_1K
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(model))
+        .map(|&(_, price)| price)
+}
+
+/// Names accepted by `model_input_price_per_1k`.
+pub fn priced_models() -> Vec<&'static str> {
+    MODEL_INPUT_PRICES_PER_1K
+        .iter()
+        .map(|&(name, _)| name)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_prefix_ends_on_a_char_boundary() {
+        let text = "héllo wörld ünïcödé ".repeat(20);
+        let total = estimate_tokens(&text).unwrap();
+        for max_tokens in 1..total {
+            let cut = token_prefix_len(&text, max_tokens).unwrap();
+            assert!(text.is_char_boundary(cut), "cut {} splits a char", cut);
+            assert!(estimate_tokens(&text[..cut]).unwrap() <= max_tokens);
+        }
+        assert_eq!(token_prefix_len(&text, total).unwrap(), text.len());
+    }
+}
@@ -0,0 +1,406 @@
+use crate::config::ContentTransform;
+use crate::headers::HASH_COMMENT_EXTENSIONS;
+use std::path::Path;
+
+// How comments are written for a family of file types, as understood by `strip_comments`
+struct CommentSyntax {
+    line: Option<&'static str>,
+    block: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+    char_literals: bool,    // Rust-style 'x' literals, told apart from lifetimes
+    triple_quotes: bool,    // Python/TOML ''' and """ strings, which may span lines
+    contractions: bool,     // An apostrophe inside a word (`don't`) is prose, not a quote
+    line_after_space: bool, // Line comments only at line start or after whitespace (shell `$#`, `${#a}`)
+}
+
+const HASH: CommentSyntax = CommentSyntax {
+    line: Some("#"),
+    block: None,
+    quotes: &['"', '\''],
+    char_literals: false,
+    triple_quotes: true,
+    contractions: true,
+    line_after_space: true,
+};
+const C_STYLE: CommentSyntax = CommentSyntax {
+    line: Some("//"),
+    block: Some(("/*", "*/")),
+    quotes: &['"', '\'', '`'],
+    char_literals: false,
+    triple_quotes: false,
+    contractions: false,
+    line_after_space: false,
+};
+const RUST: CommentSyntax = CommentSyntax {
+    line: Some("//"),
+    block: Some(("/*", "*/")),
+    quotes: &['"'],
+    char_literals: true,
+    triple_quotes: false,
+    contractions: false,
+    line_after_space: false,
+};
+const CSS: CommentSyntax = CommentSyntax {
+    line: None,
+    block: Some(("/*", "*/")),
+    quotes: &['"', '\''],
+    char_literals: false,
+    triple_quotes: false,
+    contractions: false,
+    line_after_space: false,
+};
+const SQL: CommentSyntax = CommentSyntax {
+    line: Some("--"),
+    block: Some(("/*", "*/")),
+    quotes: &['"', '\''],
+    char_literals: false,
+    triple_quotes: false,
+    contractions: false,
+    line_after_space: false,
+};
+const MARKUP: CommentSyntax = CommentSyntax {
+    line: None,
+    block: Some(("<!--", "-->")),
+    quotes: &[],
+    char_literals: false,
+    triple_quotes: false,
+    contractions: false,
+    line_after_space: false,
+};
+
+/// Applies `transform` to the content of the file at `path`.
+pub fn apply_transform(transform: ContentTransform, path: &Path, content: &str) -> String {
+    match transform {
+        ContentTransform::StripComments => strip_comments(path, content),
+        ContentTransform::MinifyWhitespace => minify_whitespace(content),
+        ContentTransform::RedactEnv => redact_env_content(content),
+        ContentTransform::None => content.to_string(),
+    }
+}
+
+// Heuristic, string-aware comment removal; lines left empty are dropped and blank runs collapsed
+pub fn strip_comments(path: &Path, content: &str) -> String {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    let syntax = match extension.as_str() {
+        "rs" => &RUST,
+        "css" => &CSS,
+        "sql" => &SQL,
+        "html" | "htm" | "xml" | "svg" | "md" | "markdown" => &MARKUP,
+        ext if HASH_COMMENT_EXTENSIONS.contains(&ext) => &HASH,
+        _ => &C_STYLE,
+    };
+
+    // Keep a shebang even though it looks like a '#' comment
+    let (shebang, body) = match content.strip_prefix("#!") {
+        Some(_) => content.split_at(content.find('\n').map_or(content.len(), |i| i + 1)),
+        None => ("", content),
+    };
+    let stripped = remove_comments(body, syntax);
+    let mut out = String::from(shebang);
+    let mut previous_blank = true;
+    for line in stripped.lines().map(str::trim_end) {
+        if line.is_empty() && previous_blank {
+            continue;
+        }
+        previous_blank = line.is_empty();
+        out.push_str(line);
+        out.push('\n');
+    }
+    if !content.ends_with('\n') && out.ends_with('\n') {
+        out.pop();
+    }
+    out
+}
+
+fn remove_comments(content: &str, syntax: &CommentSyntax) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    let mut in_string: Option<char> = None;
+    while let Some(c) = rest.chars().next() {
+        let len = c.len_utf8();
+        if let Some(quote) = in_string {
+            if c == '\\' {
+                let escaped = rest[len..].chars().next().map_or(0, char::len_utf8);
+                out.push_str(&rest[..len + escaped]);
+                rest = &rest[len + escaped..];
+                continue;
+            }
+            // Only template literals span lines, so a stray quote cannot swallow the rest of the file
+            if c == quote || (c == '\n' && quote != '`') {
+                in_string = None;
+            }
+            out.push(c);
+            rest = &rest[len..];
+            continue;
+        }
+        if let Some(delimiter) = ["\"\"\"", "'''"]
+            .into_iter()
+            .find(|d| syntax.triple_quotes && rest.starts_with(d))
+        {
+            let end = rest[3..].find(delimiter).map_or(rest.len(), |i| i + 6);
+            out.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+        if syntax.line.is_some_and(|marker| rest.starts_with(marker))
+            && (!syntax.line_after_space || out.chars().last().is_none_or(char::is_whitespace))
+        {
+            rest = &rest[rest.find('\n').unwrap_or(rest.len())..];
+            continue;
+        }
+        if let Some((open, close)) = syntax.block.filter(|(open, _)| rest.starts_with(open)) {
+            rest = match rest[open.len()..].find(close) {
+                Some(end) => &rest[open.len() + end + close.len()..],
+                None => "",
+            };
+            continue;
+        }
+        if syntax.char_literals && c == '\'' {
+            if let Some(literal) = char_literal(rest) {
+                out.push_str(literal);
+                rest = &rest[literal.len()..];
+                continue;
+            }
+        } else if syntax.quotes.contains(&c)
+            && !(syntax.contractions && c == '\'' && is_contraction(&out, &rest[len..]))
+        {
+            in_string = Some(c);
+        }
+        out.push(c);
+        rest = &rest[len..];
+    }
+    out
+}
+
+// `don't`, `it's`: letters on both sides, with at least two before so prefixes like f'..' still
+// open a string
+fn is_contraction(before: &str, after: &str) -> bool {
+    let mut preceding = before.chars().rev();
+    let letters_before = preceding.next().is_some_and(char::is_alphabetic)
+        && preceding.next().is_some_and(char::is_alphabetic);
+    letters_before && after.chars().next().is_some_and(char::is_alphabetic)
+}
+
+// Matches 'x' or an escape such as '\n' / '\u{1F600}' at the start of `text`
+fn char_literal(text: &str) -> Option<&str> {
+    let inner = text.strip_prefix('\'')?;
+    let body_len = if inner.starts_with('\\') {
+        inner.get(2..)?.find('\'')? + 2
+    } else {
+        inner.chars().next()?.len_utf8()
+    };
+    (inner.get(body_len..)?.starts_with('\'') && body_len <= 12).then(|| &text[..body_len + 2])
+}
+
+// Trailing whitespace and blank lines go; indentation is kept for indentation-sensitive languages
+pub fn minify_whitespace(content: &str) -> String {
+    content
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Keeps comments, blank lines and the `KEY=` of each assignment (optionally `export `-prefixed);
+// every value becomes `[REDACTED]`. Any other line, including every line of a multi-line quoted
+// value, is redacted entirely since it cannot be told apart from a value.
+pub fn redact_env_content(content: &str) -> String {
+    let mut open_quote: Option<char> = None;
+    let mut redacted: String = content
+        .lines()
+        .map(|line| {
+            if let Some(quote) = open_quote {
+                if closes_quote(line, quote) {
+                    open_quote = None;
+                }
+                return "[REDACTED]".to_string();
+            }
+            let trimmed = line.trim_start();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                return line.to_string();
+            }
+            match line.split_once('=').filter(|(key, _)| is_env_key(key)) {
+                Some((key, value)) => {
+                    let value = value.trim_start();
+                    open_quote = value
+                        .chars()
+                        .next()
+                        .filter(|q| matches!(q, '"' | '\''))
+                        .filter(|&q| !closes_quote(&value[1..], q));
+                    format!("{}=[REDACTED]", key.trim_end())
+                }
+                None => "[REDACTED]".to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if content.ends_with('\n') {
+        redacted.push('\n');
+    }
+    redacted
+}
+
+// `NAME` or `export NAME`, where NAME is `[A-Za-z_][A-Za-z0-9_.]*`
+fn is_env_key(key: &str) -> bool {
+    let key = key.trim();
+    let name = key
+        .strip_prefix("export")
+        .filter(|rest| rest.starts_with(char::is_whitespace))
+        .map_or(key, str::trim_start);
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+// Whether `text` contains `quote` closing a value; backslash escapes only count in double quotes
+fn closes_quote(text: &str, quote: char) -> bool {
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' && quote == '"' {
+            chars.next();
+        } else if c == quote {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strip(file: &str, content: &str) -> String {
+        strip_comments(Path::new(file), content)
+    }
+
+    #[test]
+    fn hash_comments() {
+        assert_eq!(
+            strip("run.sh", "#!/bin/sh\n# setup\necho \"# kept\" # gone\n"),
+            "#!/bin/sh\necho \"# kept\"\n"
+        );
+        assert_eq!(strip("app.py", "x = '#not' # comment\n"), "x = '#not'\n");
+    }
+
+    #[test]
+    fn hash_comments_need_a_word_boundary() {
+        assert_eq!(
+            strip("count.sh", "echo ${#arr[@]} $# # count\nurl=a#b\n"),
+            "echo ${#arr[@]} $#\nurl=a#b\n"
+        );
+    }
+
+    #[test]
+    fn hash_apostrophes_in_comments_and_prose() {
+        assert_eq!(
+            strip("a.yml", "# don't touch\nkey: 'v # x'\n"),
+            "key: 'v # x'\n"
+        );
+        assert_eq!(
+            strip("a.yml", "title: it's fine # note\nurl: 'a#b' # tail\n"),
+            "title: it's fine\nurl: 'a#b'\n"
+        );
+        assert_eq!(strip("a.py", "s = f'{x}' # c\n"), "s = f'{x}'\n");
+    }
+
+    #[test]
+    fn hash_triple_quoted_strings_span_lines() {
+        let python =
+            "def f():\n    \"\"\"Doc # kept\n    still # kept\"\"\"\n    return 1  # gone\n";
+        assert_eq!(
+            strip("m.py", python),
+            "def f():\n    \"\"\"Doc # kept\n    still # kept\"\"\"\n    return 1\n"
+        );
+        assert_eq!(
+            strip("c.toml", "a = '''\n# kept\n'''\n# gone\nb = 1\n"),
+            "a = '''\n# kept\n'''\n\nb = 1\n"
+        );
+    }
+
+    #[test]
+    fn unbalanced_quotes_end_at_the_line() {
+        assert_eq!(
+            strip("a.sh", "echo \"open\n# comment\necho ok\n"),
+            "echo \"open\n\necho ok\n"
+        );
+        assert_eq!(
+            strip("a.js", "let s = 'oops\n// comment\nlet t = 1;\n"),
+            "let s = 'oops\n\nlet t = 1;\n"
+        );
+    }
+
+    #[test]
+    fn c_style_comments() {
+        assert_eq!(
+            strip(
+                "a.js",
+                "/* head */\nconst url = \"http://x\"; // tail\nconst t = `a\n// kept`;\n"
+            ),
+            "const url = \"http://x\";\nconst t = `a\n// kept`;\n"
+        );
+    }
+
+    #[test]
+    fn rust_comments_and_char_literals() {
+        assert_eq!(
+            strip(
+                "lib.rs",
+                "/// doc\nfn f<'a>(s: &'a str) -> char { '/' } // end\nlet q = '\"'; // x\n"
+            ),
+            "fn f<'a>(s: &'a str) -> char { '/' }\nlet q = '\"';\n"
+        );
+    }
+
+    #[test]
+    fn css_sql_and_markup_comments() {
+        assert_eq!(
+            strip(
+                "a.css",
+                "a { color: red; } /* x */\nb::before { content: \"/*\"; }\n"
+            ),
+            "a { color: red; }\nb::before { content: \"/*\"; }\n"
+        );
+        assert_eq!(
+            strip("q.sql", "-- header\nSELECT '--not' FROM t; /* c */\n"),
+            "SELECT '--not' FROM t;\n"
+        );
+        assert_eq!(
+            strip("p.html", "<!-- c -->\n<p>don't</p>\n"),
+            "<p>don't</p>\n"
+        );
+    }
+
+    #[test]
+    fn redact_env_keeps_keys_and_comments() {
+        assert_eq!(
+            redact_env_content(
+                "# db\nexport DB_URL=postgres://x\n\nAPI.KEY = \"s=1\"\nnot a pair\n"
+            ),
+            "# db\nexport DB_URL=[REDACTED]\n\nAPI.KEY=[REDACTED]\n[REDACTED]\n"
+        );
+    }
+
+    #[test]
+    fn redact_env_multiline_values() {
+        let env = "KEY=\"-----BEGIN KEY-----\nMIIBOgIBAAJBAKj34GkxFhD90vcNLYLInFEX6Qu=\nabc\\\"def==\n-----END KEY-----\"\nNEXT='a\nb=c'\nLAST=1\n";
+        assert_eq!(
+            redact_env_content(env),
+            "KEY=[REDACTED]\n[REDACTED]\n[REDACTED]\n[REDACTED]\nNEXT=[REDACTED]\n[REDACTED]\nLAST=[REDACTED]\n"
+        );
+    }
+
+    #[test]
+    fn redact_env_rejects_non_key_left_sides() {
+        assert_eq!(
+            redact_env_content("MIIB+/x=\n1KEY=v\n"),
+            "[REDACTED]\n[REDACTED]\n"
+        );
+    }
+}